@@ -0,0 +1,250 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An exported function as found in the sources
+struct ExportedFunction {
+    name: String,
+    /// The calling convention, `Rust` if none is declared
+    abi: String,
+    parameters: Vec<(String, String)>,
+    return_type: String,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+
+    let mut sources = vec![];
+    collect_sources(&crate_dir.join("src"), &mut sources);
+    sources.sort();
+
+    let mut functions = vec![];
+    for source in sources {
+        if !is_compiled_for(&source, &target_os) {
+            continue;
+        }
+        let contents = fs::read_to_string(&source).unwrap();
+        functions.extend(exported_functions(&contents));
+    }
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+    functions.dedup_by(|a, b| a.name == b.name);
+
+    fs::write(
+        out_dir.join("ffi_functions.rs"),
+        ffi_functions_source(&functions),
+    )
+    .unwrap();
+
+    generate_header(&crate_dir, &out_dir);
+}
+
+fn collect_sources(directory: &Path, sources: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_sources(&path, sources);
+        } else if path
+            .extension()
+            .map_or(false, |extension| extension == "rs")
+        {
+            sources.push(path);
+        }
+    }
+}
+
+/// Only one of the platform extensions is compiled, see `lib.rs`
+fn is_compiled_for(source: &Path, target_os: &str) -> bool {
+    let is_platform = source
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map_or(false, |parent| parent == "platform");
+    if !is_platform {
+        return true;
+    }
+    let platform = match target_os {
+        "macos" => "macos",
+        "linux" => "linux",
+        _ => "others",
+    };
+    source.file_stem().map_or(false, |stem| stem == platform)
+}
+
+fn exported_functions(contents: &str) -> Vec<ExportedFunction> {
+    let mut functions = vec![];
+    let mut remaining = contents;
+    while let Some(index) = remaining.find("#[no_mangle]") {
+        remaining = &remaining[index + "#[no_mangle]".len()..];
+        let start = match remaining.find("fn ") {
+            None => break,
+            Some(start) => start + "fn ".len(),
+        };
+        let end = match remaining.find('{') {
+            None => break,
+            Some(end) => end,
+        };
+        if start < end {
+            if let Some(mut function) = parse_signature(&remaining[start..end]) {
+                function.abi = parse_abi(&remaining[..start]);
+                functions.push(function);
+            }
+        }
+    }
+    functions
+}
+
+/// Parse the calling convention from the qualifiers before `fn`, such as `pub extern "C"`
+fn parse_abi(qualifiers: &str) -> String {
+    match qualifiers.rfind("extern") {
+        None => "Rust".to_string(),
+        Some(index) => {
+            let abi = qualifiers[index + "extern".len()..]
+                .trim_start()
+                .split('"')
+                .nth(1)
+                .unwrap_or("C");
+            abi.to_string()
+        }
+    }
+}
+
+/// Parse `name(parameters) -> return_type` of a function
+fn parse_signature(signature: &str) -> Option<ExportedFunction> {
+    let open = signature.find('(')?;
+    let name = signature[..open].trim().to_string();
+
+    let mut depth = 0;
+    let mut close = None;
+    for (index, character) in signature.char_indices().skip(open) {
+        match character {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+
+    let parameters = split_top_level(&signature[open + 1..close])
+        .into_iter()
+        .filter_map(|parameter| {
+            let separator = parameter.find(':')?;
+            Some((
+                normalize(&parameter[..separator]),
+                normalize(&parameter[separator + 1..]),
+            ))
+        })
+        .collect();
+
+    let return_type = signature[close + 1..]
+        .trim()
+        .strip_prefix("->")
+        .map_or_else(|| "()".to_string(), normalize);
+
+    Some(ExportedFunction {
+        name,
+        abi: String::new(),
+        parameters,
+        return_type,
+    })
+}
+
+/// Split parameters on commas that are not nested in generics or function types
+fn split_top_level(parameters: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    let mut previous = ' ';
+    for (index, character) in parameters.char_indices() {
+        match character {
+            '<' | '(' | '[' => depth += 1,
+            // the arrow of a return type is not a closing bracket
+            '>' if previous != '-' => depth -= 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(&parameters[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+        previous = character;
+    }
+    result.push(&parameters[start..]);
+    result
+        .into_iter()
+        .filter(|parameter| !parameter.trim().is_empty())
+        .collect()
+}
+
+/// Collapse the whitespace of a type as it is written across lines
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn ffi_functions_source(functions: &[ExportedFunction]) -> String {
+    let mut source = String::new();
+    source.push_str("pub const FFI_FUNCTIONS: &[FfiFunction] = &[\n");
+    for function in functions {
+        source.push_str(&format!(
+            "    FfiFunction {{ name: {:?}, abi: {:?}, parameters: &[",
+            function.name, function.abi
+        ));
+        for (name, type_name) in &function.parameters {
+            source.push_str(&format!(
+                "FfiParameter {{ name: {:?}, type_name: {:?} }}, ",
+                name, type_name
+            ));
+        }
+        source.push_str(&format!("], return_type: {:?} }},\n", function.return_type));
+    }
+    source.push_str("];\n");
+    source
+}
+
+/// Generate the C header of the exported functions into `OUT_DIR` and copy it to
+/// `<target>/<profile>/include/Glutin.h`, next to the built library, where packaging finds it
+/// without knowing the hash of the build directory. The header is a convenience for the host
+/// bindings, a failure to generate it must not break the build of the library.
+fn generate_header(crate_dir: &Path, out_dir: &Path) {
+    let config = cbindgen::Config::from_root_or_default(crate_dir);
+    let bindings = match cbindgen::generate_with_config(crate_dir, config) {
+        Ok(bindings) => bindings,
+        Err(error) => {
+            println!("cargo:warning=Could not generate the C header: {}", error);
+            return;
+        }
+    };
+
+    let header = out_dir.join("Glutin.h");
+    bindings.write_to_file(&header);
+
+    if let Some(include_dir) = header_include_dir(out_dir) {
+        if let Err(error) = fs::create_dir_all(&include_dir)
+            .and_then(|_| fs::copy(&header, include_dir.join("Glutin.h")))
+        {
+            println!(
+                "cargo:warning=Could not copy the C header to {}: {}",
+                include_dir.display(),
+                error
+            );
+        }
+    }
+}
+
+/// `OUT_DIR` is `<target>/<profile>/build/<package>-<hash>/out`, the include directory
+/// is placed in the profile directory three levels above it
+fn header_include_dir(out_dir: &Path) -> Option<PathBuf> {
+    out_dir
+        .ancestors()
+        .nth(3)
+        .map(|profile_dir| profile_dir.join("include"))
+}
@@ -0,0 +1,121 @@
+use glutin::event_loop::EventLoop;
+use value_box::ValueBox;
+
+use crate::event_loop::{GlutinEventLoop, GlutinLoopEvent};
+use crate::thread_guard::GlutinThreadGuardedPointer;
+
+/// Optional features that depend on the platform the library is compiled for
+/// and on the windowing system it runs on
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GlutinCapability {
+    /// Windows on a Wayland compositor
+    Wayland,
+    /// Headless contexts without a surface
+    Surfaceless,
+    /// Headless contexts rendered in software by OSMesa
+    OsMesa,
+    /// Positioning of the input method editor with `glutin_windowed_context_set_ime_position`.
+    /// Composition (preedit and commit) events are not available, composed text arrives
+    /// as `WindowEventReceivedCharacter` events.
+    ImePosition,
+    /// Device events such as raw mouse motion, independent of the focused window
+    RawDeviceEvents,
+    /// Borderless fullscreen windows with `glutin_windowed_context_set_fullscreen`
+    Fullscreen,
+}
+
+/// Return true if the support of the capability is compiled into the library.
+/// A compiled capability may still be unavailable with the windowing system
+/// the library runs on, see `glutin_events_loop_has_capability`.
+#[no_mangle]
+pub extern "C" fn glutin_is_capability_compiled(capability: GlutinCapability) -> bool {
+    match capability {
+        GlutinCapability::Wayland | GlutinCapability::Surfaceless | GlutinCapability::OsMesa => {
+            cfg!(target_os = "linux")
+        }
+        GlutinCapability::ImePosition
+        | GlutinCapability::RawDeviceEvents
+        | GlutinCapability::Fullscreen => cfg!(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "windows"
+        )),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_capability_available(
+    event_loop: &EventLoop<GlutinLoopEvent>,
+    capability: GlutinCapability,
+) -> bool {
+    use crate::headless_context::glutin_is_surfaceless_available;
+    use glutin::platform::unix::EventLoopWindowTargetExtUnix;
+    match capability {
+        GlutinCapability::Wayland => event_loop.is_wayland(),
+        GlutinCapability::Surfaceless => glutin_is_surfaceless_available(event_loop),
+        // glutin loads libOSMesa only when an OSMesa context is built
+        GlutinCapability::OsMesa => osmesa_sys::OsMesa::try_loading().is_ok(),
+        _ => glutin_is_capability_compiled(capability),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_capability_available(
+    _event_loop: &EventLoop<GlutinLoopEvent>,
+    capability: GlutinCapability,
+) -> bool {
+    glutin_is_capability_compiled(capability)
+}
+
+/// Return true if the capability can be used with the windowing system of the event loop.
+/// The mock backend only supports the input method editor position and fullscreen windows.
+/// Surfaceless support is checked by building a surfaceless context.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_has_capability(
+    _ptr_event_loop: *mut ValueBox<GlutinEventLoop>,
+    capability: GlutinCapability,
+) -> bool {
+    _ptr_event_loop.with_owned_return(false, |event_loop| match event_loop.native() {
+        None => matches!(
+            capability,
+            GlutinCapability::ImePosition | GlutinCapability::Fullscreen
+        ),
+        Some(event_loop) => is_capability_available(event_loop, capability),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_loop::glutin_destroy_events_loop;
+    use crate::mock::fixtures::create_mock_event_loop;
+
+    #[test]
+    fn mock_backend_has_no_windowing_system_capabilities() {
+        let event_loop = create_mock_event_loop();
+
+        assert!(glutin_events_loop_has_capability(
+            event_loop,
+            GlutinCapability::ImePosition
+        ));
+        assert!(glutin_events_loop_has_capability(
+            event_loop,
+            GlutinCapability::Fullscreen
+        ));
+        assert!(!glutin_events_loop_has_capability(
+            event_loop,
+            GlutinCapability::Wayland
+        ));
+        assert!(!glutin_events_loop_has_capability(
+            event_loop,
+            GlutinCapability::Surfaceless
+        ));
+        assert!(!glutin_events_loop_has_capability(
+            std::ptr::null_mut(),
+            GlutinCapability::ImePosition
+        ));
+
+        glutin_destroy_events_loop(event_loop);
+    }
+}
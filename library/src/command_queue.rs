@@ -0,0 +1,331 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use string_box::StringBox;
+use value_box::{ValueBox, ValueBoxPointer};
+
+use crate::enums::GlutinResult;
+use crate::event_loop::{GlutinEventLoop, GlutinEventLoopProxy, GlutinLoopEvent};
+use crate::thread_guard::GlutinThreadGuardedPointer;
+use crate::windowed_context::{
+    glutin_windowed_context_request_redraw, glutin_windowed_context_set_inner_size,
+    glutin_windowed_context_set_title, GlutinWindowedContext,
+};
+
+/// A window operation requested by another thread
+#[derive(Debug)]
+enum GlutinWindowCommand {
+    SetTitle(String),
+    SetInnerSize(u32, u32),
+    RequestRedraw,
+}
+
+/// A window as passed by the host together with its identity when the command was queued.
+/// It is only dereferenced on the thread of the event loop and only if the window is still
+/// alive with the same identity, a destroyed window may leave its address to a new one.
+#[derive(Debug)]
+struct GlutinQueuedWindow {
+    window: *mut ValueBox<GlutinWindowedContext>,
+    identity: u64,
+}
+
+unsafe impl Send for GlutinQueuedWindow {}
+
+#[derive(Debug)]
+struct GlutinCommandQueueState {
+    commands: VecDeque<(GlutinQueuedWindow, GlutinWindowCommand)>,
+    /// Wakes up a native event loop that is waiting for events
+    proxy: Option<GlutinEventLoopProxy>,
+}
+
+/// Window operations queued by any thread and performed by the thread of the event loop
+/// at the start of the next iteration of the loop
+#[derive(Debug, Clone)]
+pub struct GlutinCommandQueue {
+    state: Arc<Mutex<GlutinCommandQueueState>>,
+}
+
+impl GlutinCommandQueue {
+    pub fn new(proxy: Option<GlutinEventLoopProxy>) -> Self {
+        let state = GlutinCommandQueueState {
+            commands: VecDeque::new(),
+            proxy,
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    fn push(
+        &self,
+        window: *mut ValueBox<GlutinWindowedContext>,
+        command: GlutinWindowCommand,
+    ) -> GlutinResult {
+        // the identity is known without reading the window, which belongs to another thread
+        let identity = match window.owned_identity() {
+            Some(identity) => identity,
+            None => {
+                error!("Can not queue a command for a destroyed window");
+                return GlutinResult::NullPointer;
+            }
+        };
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        state
+            .commands
+            .push_back((GlutinQueuedWindow { window, identity }, command));
+        if let Some(proxy) = &state.proxy {
+            if proxy.send_event(GlutinLoopEvent::CommandsQueued).is_err() {
+                warn!("Could not wake up the event loop, it is closed");
+            }
+        }
+        GlutinResult::Ok
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state
+            .lock()
+            .map_or(true, |state| state.commands.is_empty())
+    }
+
+    /// Perform the queued commands, must be called by the thread of the event loop
+    pub fn perform(&self) {
+        // take the commands first, performing them may queue new ones
+        let commands = match self.state.lock() {
+            Ok(mut state) => std::mem::take(&mut state.commands),
+            Err(_) => return,
+        };
+
+        for (GlutinQueuedWindow { window, identity }, command) in commands {
+            if window.owned_identity() != Some(identity) {
+                debug!(
+                    "Dropped a queued command for a destroyed window: {:?}",
+                    command
+                );
+                continue;
+            }
+            let result = match command {
+                GlutinWindowCommand::SetTitle(title) => {
+                    let title = ValueBox::new(StringBox::from_string(title)).into_raw();
+                    let result = glutin_windowed_context_set_title(window, title);
+                    title.release();
+                    result
+                }
+                GlutinWindowCommand::SetInnerSize(width, height) => {
+                    glutin_windowed_context_set_inner_size(window, width, height)
+                }
+                GlutinWindowCommand::RequestRedraw => {
+                    glutin_windowed_context_request_redraw(window)
+                }
+            };
+            if result != GlutinResult::Ok {
+                error!("Failed to perform a queued window command: {:?}", result);
+            }
+        }
+    }
+}
+
+/// Return a handle to the queue of window commands of the event loop that can be moved
+/// to another thread. Every thread needs its own handle, see `glutin_command_queue_clone`.
+/// Must be called by the thread of the event loop, release it with `glutin_command_queue_drop`.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_get_command_queue(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> *mut ValueBox<GlutinCommandQueue> {
+    _ptr_events_loop.with_owned_return(std::ptr::null_mut(), |event_loop| {
+        ValueBox::new(event_loop.commands.clone()).into_raw()
+    })
+}
+
+/// Queue setting the title of a window. The command is dropped if the window is destroyed
+/// before the event loop performs it.
+#[no_mangle]
+pub extern "C" fn glutin_command_queue_set_title(
+    _ptr_command_queue: *mut ValueBox<GlutinCommandQueue>,
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    _ptr_title: *mut ValueBox<StringBox>,
+) -> GlutinResult {
+    if _ptr_window.is_null() {
+        return GlutinResult::NullPointer;
+    }
+    _ptr_command_queue.with_not_null_return(GlutinResult::NullPointer, |command_queue| {
+        _ptr_title.with_not_null_return(GlutinResult::NullPointer, |title| {
+            command_queue.push(
+                _ptr_window,
+                GlutinWindowCommand::SetTitle(title.to_string()),
+            )
+        })
+    })
+}
+
+/// Queue resizing the inner area of a window to a given physical size
+#[no_mangle]
+pub extern "C" fn glutin_command_queue_set_inner_size(
+    _ptr_command_queue: *mut ValueBox<GlutinCommandQueue>,
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    width: u32,
+    height: u32,
+) -> GlutinResult {
+    if _ptr_window.is_null() {
+        return GlutinResult::NullPointer;
+    }
+    _ptr_command_queue.with_not_null_return(GlutinResult::NullPointer, |command_queue| {
+        command_queue.push(
+            _ptr_window,
+            GlutinWindowCommand::SetInnerSize(width, height),
+        )
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_command_queue_request_redraw(
+    _ptr_command_queue: *mut ValueBox<GlutinCommandQueue>,
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    if _ptr_window.is_null() {
+        return GlutinResult::NullPointer;
+    }
+    _ptr_command_queue.with_not_null_return(GlutinResult::NullPointer, |command_queue| {
+        command_queue.push(_ptr_window, GlutinWindowCommand::RequestRedraw)
+    })
+}
+
+/// Return another handle to the same queue, for use by another thread
+#[no_mangle]
+pub extern "C" fn glutin_command_queue_clone(
+    _ptr_command_queue: *mut ValueBox<GlutinCommandQueue>,
+) -> *mut ValueBox<GlutinCommandQueue> {
+    _ptr_command_queue.with_not_null_return(std::ptr::null_mut(), |command_queue| {
+        ValueBox::new(command_queue.clone()).into_raw()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_command_queue_drop(_ptr: *mut ValueBox<GlutinCommandQueue>) {
+    _ptr.release();
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry_box::SizeBox;
+
+    use super::*;
+    use crate::event_loop::glutin_destroy_events_loop;
+    use crate::events::GlutinControlFlow;
+    use crate::mock::fixtures::*;
+    use crate::mock::glutin_mock_window_get_title;
+    use crate::windowed_context::{
+        glutin_destroy_windowed_context, glutin_windowed_context_get_inner_size,
+        glutin_windowed_context_set_title,
+    };
+
+    #[test]
+    fn other_threads_queue_window_commands() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let command_queue = glutin_events_loop_get_command_queue(event_loop);
+
+        // raw pointers are not Send, the test passes them as addresses
+        let thread_queue = glutin_command_queue_clone(command_queue) as usize;
+        let thread_window = window as usize;
+        let thread_event_loop = event_loop as usize;
+        std::thread::spawn(move || {
+            let command_queue = thread_queue as *mut ValueBox<GlutinCommandQueue>;
+            let window = thread_window as *mut ValueBox<GlutinWindowedContext>;
+            let event_loop = thread_event_loop as *mut ValueBox<GlutinEventLoop>;
+            let title = ValueBox::new(StringBox::from_string(String::from("Queued"))).into_raw();
+
+            assert_eq!(
+                glutin_windowed_context_set_title(window, title),
+                GlutinResult::WrongThread
+            );
+            assert_eq!(
+                glutin_destroy_events_loop(event_loop),
+                GlutinResult::WrongThread
+            );
+            assert_eq!(
+                glutin_command_queue_set_title(command_queue, window, title),
+                GlutinResult::Ok
+            );
+            assert_eq!(
+                glutin_command_queue_set_inner_size(command_queue, window, 64, 48),
+                GlutinResult::Ok
+            );
+
+            title.release();
+            glutin_command_queue_drop(command_queue);
+        })
+        .join()
+        .unwrap();
+
+        let title = glutin_mock_window_get_title(window);
+        assert_ne!(
+            title.with_not_null_return(String::new(), |title| title.to_string()),
+            "Queued"
+        );
+        title.release();
+
+        event_loop.with_not_null(|event_loop| {
+            event_loop.pump_events(std::time::Duration::ZERO, |_| GlutinControlFlow::Poll);
+        });
+
+        let title = glutin_mock_window_get_title(window);
+        assert_eq!(
+            title.with_not_null_return(String::new(), |title| title.to_string()),
+            "Queued"
+        );
+        title.release();
+        let size = ValueBox::new(SizeBox::<u32>::default()).into_raw();
+        glutin_windowed_context_get_inner_size(window, size);
+        assert_eq!(
+            size.with_not_null_return((0, 0), |size| (size.width, size.height)),
+            (64, 48)
+        );
+        size.release();
+
+        glutin_command_queue_drop(command_queue);
+        assert_eq!(glutin_destroy_windowed_context(window), GlutinResult::Ok);
+        assert_eq!(glutin_destroy_events_loop(event_loop), GlutinResult::Ok);
+    }
+
+    #[test]
+    fn commands_for_destroyed_windows_are_dropped() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let command_queue = glutin_events_loop_get_command_queue(event_loop);
+        let title = ValueBox::new(StringBox::from_string(String::from("Queued"))).into_raw();
+
+        assert_eq!(
+            glutin_command_queue_set_title(command_queue, window, title),
+            GlutinResult::Ok
+        );
+        assert_eq!(glutin_destroy_windowed_context(window), GlutinResult::Ok);
+        assert_eq!(
+            glutin_command_queue_set_title(command_queue, window, title),
+            GlutinResult::NullPointer
+        );
+
+        // the new window may reuse the address of the destroyed one
+        let new_window = create_mock_window(event_loop);
+        event_loop.with_not_null(|event_loop| {
+            event_loop.pump_events(std::time::Duration::ZERO, |_| GlutinControlFlow::Poll);
+        });
+
+        let new_title = glutin_mock_window_get_title(new_window);
+        assert_ne!(
+            new_title.with_not_null_return(String::new(), |title| title.to_string()),
+            "Queued"
+        );
+        new_title.release();
+
+        title.release();
+        glutin_command_queue_drop(command_queue);
+        assert_eq!(
+            glutin_destroy_windowed_context(new_window),
+            GlutinResult::Ok
+        );
+        assert_eq!(glutin_destroy_events_loop(event_loop), GlutinResult::Ok);
+    }
+}
@@ -73,12 +73,12 @@ macro_rules! with_builder {
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_default() -> *mut ValueBox<GlutinContextBuilder> {
+pub extern "C" fn glutin_context_builder_default() -> *mut ValueBox<GlutinContextBuilder> {
     ValueBox::new(GlutinContextBuilder::NotCurrent(ContextBuilder::new())).into_raw()
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_shared_headless_context(
+pub extern "C" fn glutin_context_builder_with_shared_headless_context(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     another_context: *mut ValueBox<GlutinHeadlessContext>,
 ) {
@@ -92,7 +92,7 @@ pub fn glutin_context_builder_with_shared_headless_context(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_gl_then_gles(
+pub extern "C" fn glutin_context_builder_with_gl_then_gles(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     gl_major: u8,
     gl_minor: u8,
@@ -113,14 +113,16 @@ pub fn glutin_context_builder_with_gl_then_gles(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_gl_latest(context_builder: *mut ValueBox<GlutinContextBuilder>) {
+pub extern "C" fn glutin_context_builder_with_gl_latest(
+    context_builder: *mut ValueBox<GlutinContextBuilder>,
+) {
     context_builder
         .replace_value(|builder| with_builder!(builder, builder.with_gl(GlRequest::Latest)))
         .log();
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_gl_profile_core(
+pub extern "C" fn glutin_context_builder_with_gl_profile_core(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
 ) {
     context_builder
@@ -129,7 +131,7 @@ pub fn glutin_context_builder_with_gl_profile_core(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_gl_profile_compatibility(
+pub extern "C" fn glutin_context_builder_with_gl_profile_compatibility(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
 ) {
     context_builder
@@ -140,7 +142,7 @@ pub fn glutin_context_builder_with_gl_profile_compatibility(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_multisampling(
+pub extern "C" fn glutin_context_builder_with_multisampling(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     samples: u16,
 ) {
@@ -150,7 +152,7 @@ pub fn glutin_context_builder_with_multisampling(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_depth_buffer(
+pub extern "C" fn glutin_context_builder_with_depth_buffer(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     bits: u8,
 ) {
@@ -160,7 +162,7 @@ pub fn glutin_context_builder_with_depth_buffer(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_stencil_buffer(
+pub extern "C" fn glutin_context_builder_with_stencil_buffer(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     bits: u8,
 ) {
@@ -170,7 +172,7 @@ pub fn glutin_context_builder_with_stencil_buffer(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_pixel_format(
+pub extern "C" fn glutin_context_builder_with_pixel_format(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     color_bits: u8,
     alpha_bits: u8,
@@ -183,7 +185,7 @@ pub fn glutin_context_builder_with_pixel_format(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_vsync(
+pub extern "C" fn glutin_context_builder_with_vsync(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     vsync: bool,
 ) {
@@ -193,7 +195,7 @@ pub fn glutin_context_builder_with_vsync(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_srgb(
+pub extern "C" fn glutin_context_builder_with_srgb(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     srgb_enabled: bool,
 ) {
@@ -203,7 +205,7 @@ pub fn glutin_context_builder_with_srgb(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_double_buffer(
+pub extern "C" fn glutin_context_builder_with_double_buffer(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     double_buffer_enabled: bool,
 ) {
@@ -218,7 +220,7 @@ pub fn glutin_context_builder_with_double_buffer(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_hardware_acceleration(
+pub extern "C" fn glutin_context_builder_with_hardware_acceleration(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
     hardware_acceleration_enabled: bool,
 ) {
@@ -233,7 +235,7 @@ pub fn glutin_context_builder_with_hardware_acceleration(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_with_any_hardware_acceleration(
+pub extern "C" fn glutin_context_builder_with_any_hardware_acceleration(
     context_builder: *mut ValueBox<GlutinContextBuilder>,
 ) {
     context_builder
@@ -242,7 +244,7 @@ pub fn glutin_context_builder_with_any_hardware_acceleration(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_get_pixel_format_requirements(
+pub extern "C" fn glutin_context_builder_get_pixel_format_requirements(
     _ptr_context_builder: *mut ValueBox<GlutinContextBuilder>,
 ) -> *mut ValueBox<PixelFormatRequirements> {
     _ptr_context_builder.with_not_null_return(std::ptr::null_mut(), |builder| {
@@ -255,11 +257,11 @@ pub fn glutin_context_builder_get_pixel_format_requirements(
 }
 
 #[no_mangle]
-pub fn glutin_context_builder_print_it(_ptr: *mut ValueBox<GlutinContextBuilder>) {
+pub extern "C" fn glutin_context_builder_print_it(_ptr: *mut ValueBox<GlutinContextBuilder>) {
     _ptr.with_not_null(|builder| println!("{:?}", builder))
 }
 
 #[no_mangle]
-pub fn glutin_destroy_context_builder(_ptr: *mut ValueBox<GlutinContextBuilder>) {
+pub extern "C" fn glutin_destroy_context_builder(_ptr: *mut ValueBox<GlutinContextBuilder>) {
     _ptr.release();
 }
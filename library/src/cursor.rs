@@ -0,0 +1,316 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use glutin::window::{CursorIcon, Window};
+use value_box::{ValueBox, ValueBoxPointer};
+
+use native::NativeCursor;
+
+/// A cursor image created from RGBA pixel data with a hotspot.
+/// Native cursors are created lazily the first time the cursor is set on a window
+/// and belong to that window, they are freed when the window is destroyed or, once the custom
+/// cursor is destroyed, when the window changes its cursor. It is safe to destroy a custom
+/// cursor that is still displayed.
+#[derive(Debug)]
+pub struct GlutinCustomCursor {
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+    /// Non-premultiplied RGBA pixels, row by row
+    rgba: Vec<u8>,
+    /// Identifies the cursor in the caches of the windows and tells them when it is destroyed
+    token: Arc<()>,
+}
+
+/// The native cursors created for a window
+#[derive(Debug, Default)]
+struct WindowCursors {
+    native_cursors: Vec<(Weak<()>, NativeCursor)>,
+    /// winit does not know that a custom cursor replaced its cursor icon
+    is_custom_cursor_displayed: bool,
+}
+
+impl WindowCursors {
+    /// Free the native cursors of destroyed custom cursors
+    fn release_destroyed_cursors(&mut self) {
+        self.native_cursors
+            .retain(|(token, _)| token.strong_count() > 0);
+    }
+}
+
+thread_local! {
+    /// Windows belong to the thread of their event loop, so do their native cursors
+    static WINDOW_CURSORS: RefCell<HashMap<u64, WindowCursors>> = RefCell::new(HashMap::new());
+}
+
+impl GlutinCustomCursor {
+    pub fn new(
+        rgba: Vec<u8>,
+        width: u16,
+        height: u16,
+        hotspot_x: u16,
+        hotspot_y: u16,
+    ) -> Result<Self, String> {
+        let expected_length = width as usize * height as usize * 4;
+        if rgba.len() != expected_length {
+            return Err(format!(
+                "Cursor of size {}x{} must have {} bytes of RGBA data, but has {}",
+                width,
+                height,
+                expected_length,
+                rgba.len()
+            ));
+        }
+        if hotspot_x >= width || hotspot_y >= height {
+            return Err(format!(
+                "Cursor hotspot ({}, {}) is outside of the {}x{} image",
+                hotspot_x, hotspot_y, width, height
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            hotspot_x,
+            hotspot_y,
+            rgba,
+            token: Arc::new(()),
+        })
+    }
+
+    /// Set this cursor on a given window, creating and caching the native cursor if needed.
+    pub fn set_on_window(&self, window: &Window) -> Result<(), String> {
+        let key = NativeCursor::window_key(window)?;
+        WINDOW_CURSORS.with(|window_cursors| {
+            let mut window_cursors = window_cursors.borrow_mut();
+            let cursors = window_cursors.entry(key).or_default();
+            cursors.release_destroyed_cursors();
+
+            let token = Arc::downgrade(&self.token);
+            let index = match cursors
+                .native_cursors
+                .iter()
+                .position(|(cursor_token, _)| cursor_token.ptr_eq(&token))
+            {
+                Some(index) => index,
+                None => {
+                    let native_cursor = NativeCursor::create(window, self)?;
+                    cursors.native_cursors.push((token, native_cursor));
+                    cursors.native_cursors.len() - 1
+                }
+            };
+
+            cursors.native_cursors[index].1.set_on_window(window)?;
+            cursors.is_custom_cursor_displayed = true;
+            Ok(())
+        })
+    }
+}
+
+/// Return true if custom cursors can be set on the native window
+pub fn glutin_is_custom_cursor_supported(window: &Window) -> bool {
+    NativeCursor::window_key(window).is_ok()
+}
+
+/// Set a cursor icon on a native window. winit only redefines the cursor when the icon differs
+/// from the last icon it set, so after a custom cursor it is first switched to another icon.
+pub fn glutin_set_cursor_icon(window: &Window, icon: CursorIcon) {
+    let is_custom_cursor_displayed = NativeCursor::window_key(window)
+        .map(|key| {
+            WINDOW_CURSORS.with(|window_cursors| {
+                window_cursors
+                    .borrow_mut()
+                    .get_mut(&key)
+                    .map_or(false, |cursors| {
+                        cursors.release_destroyed_cursors();
+                        std::mem::take(&mut cursors.is_custom_cursor_displayed)
+                    })
+            })
+        })
+        .unwrap_or(false);
+
+    if is_custom_cursor_displayed {
+        window.set_cursor_icon(if icon == CursorIcon::Default {
+            CursorIcon::Arrow
+        } else {
+            CursorIcon::Default
+        });
+    }
+    window.set_cursor_icon(icon);
+}
+
+/// Free the native cursors of a window that is about to be destroyed,
+/// while its connection to the windowing system is still open
+pub fn glutin_release_window_cursors(window: &Window) {
+    if let Ok(key) = NativeCursor::window_key(window) {
+        WINDOW_CURSORS.with(|window_cursors| window_cursors.borrow_mut().remove(&key));
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod native {
+    use glutin::platform::unix::WindowExtUnix;
+    use glutin::window::Window;
+    use x11_dl::xlib::{Cursor, Display};
+
+    use super::GlutinCustomCursor;
+    use crate::ext::{glutin_xcursor, glutin_xlib};
+
+    /// An X11 cursor created through Xcursor. It must be dropped before its window,
+    /// which keeps the connection to the display open.
+    pub struct NativeCursor {
+        display: *mut Display,
+        cursor: Cursor,
+    }
+
+    impl NativeCursor {
+        pub fn window_key(window: &Window) -> Result<u64, String> {
+            window
+                .xlib_window()
+                .map(|window| window as u64)
+                .ok_or_else(|| String::from("Custom cursors are only supported on X11"))
+        }
+
+        pub fn create(window: &Window, custom_cursor: &GlutinCustomCursor) -> Result<Self, String> {
+            let display = window
+                .xlib_display()
+                .ok_or_else(|| String::from("Custom cursors are only supported on X11"))?
+                as *mut Display;
+
+            let xcursor = glutin_xcursor()?;
+
+            let cursor = unsafe {
+                let image = (xcursor.XcursorImageCreate)(
+                    custom_cursor.width as i32,
+                    custom_cursor.height as i32,
+                );
+                if image.is_null() {
+                    return Err(String::from("Could not allocate Xcursor image"));
+                }
+                (*image).xhot = custom_cursor.hotspot_x as u32;
+                (*image).yhot = custom_cursor.hotspot_y as u32;
+                (*image).delay = 0;
+
+                let pixels = std::slice::from_raw_parts_mut(
+                    (*image).pixels,
+                    custom_cursor.width as usize * custom_cursor.height as usize,
+                );
+                for (pixel, rgba) in pixels.iter_mut().zip(custom_cursor.rgba.chunks_exact(4)) {
+                    // Xcursor expects premultiplied ARGB
+                    let alpha = rgba[3] as u32;
+                    let red = rgba[0] as u32 * alpha / 255;
+                    let green = rgba[1] as u32 * alpha / 255;
+                    let blue = rgba[2] as u32 * alpha / 255;
+                    *pixel = (alpha << 24) | (red << 16) | (green << 8) | blue;
+                }
+
+                let cursor = (xcursor.XcursorImageLoadCursor)(display, image);
+                (xcursor.XcursorImageDestroy)(image);
+                cursor
+            };
+
+            if cursor == 0 {
+                return Err(String::from("Could not create X11 cursor"));
+            }
+
+            Ok(Self { display, cursor })
+        }
+
+        pub fn set_on_window(&self, window: &Window) -> Result<(), String> {
+            let xlib_window = window
+                .xlib_window()
+                .ok_or_else(|| String::from("Custom cursors are only supported on X11"))?;
+            let xlib = glutin_xlib()?;
+            unsafe {
+                (xlib.XDefineCursor)(self.display, xlib_window, self.cursor);
+                (xlib.XFlush)(self.display);
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for NativeCursor {
+        fn drop(&mut self) {
+            // the cursor was created with the loaded library
+            if let Ok(xlib) = glutin_xlib() {
+                unsafe {
+                    (xlib.XFreeCursor)(self.display, self.cursor);
+                }
+            }
+        }
+    }
+
+    impl std::fmt::Debug for NativeCursor {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("NativeCursor")
+                .field("cursor", &self.cursor)
+                .finish()
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod native {
+    use glutin::window::Window;
+
+    use super::GlutinCustomCursor;
+
+    #[derive(Debug)]
+    pub struct NativeCursor;
+
+    impl NativeCursor {
+        pub fn window_key(_window: &Window) -> Result<u64, String> {
+            Err(String::from(
+                "Custom cursors are not supported on this platform",
+            ))
+        }
+
+        pub fn create(
+            _window: &Window,
+            _custom_cursor: &GlutinCustomCursor,
+        ) -> Result<Self, String> {
+            Err(String::from(
+                "Custom cursors are not supported on this platform",
+            ))
+        }
+
+        pub fn set_on_window(&self, _window: &Window) -> Result<(), String> {
+            Err(String::from(
+                "Custom cursors are not supported on this platform",
+            ))
+        }
+    }
+}
+
+/// Create a custom cursor from `width * height * 4` bytes of non-premultiplied RGBA pixels.
+/// Returns null if the pixel data does not match the size or the hotspot is outside of the image.
+#[no_mangle]
+pub extern "C" fn glutin_create_custom_cursor(
+    pixels: *const u8,
+    length: usize,
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+) -> *mut ValueBox<GlutinCustomCursor> {
+    if pixels.is_null() {
+        error!("[glutin_create_custom_cursor] Pixels are null");
+        return std::ptr::null_mut();
+    }
+
+    let rgba = unsafe { std::slice::from_raw_parts(pixels, length) }.to_vec();
+    match GlutinCustomCursor::new(rgba, width, height, hotspot_x, hotspot_y) {
+        Ok(cursor) => ValueBox::new(cursor).into_raw(),
+        Err(error) => {
+            error!("[glutin_create_custom_cursor] {}", error);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_destroy_custom_cursor(_ptr: *mut ValueBox<GlutinCustomCursor>) {
+    _ptr.release();
+}
@@ -1,4 +1,63 @@
-use glutin::window::CursorIcon;
+use glutin::error::ExternalError;
+use glutin::window::{CursorIcon, UserAttentionType};
+use glutin::ContextError;
+
+/// The result of window operations that can be rejected by the windowing system
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GlutinResult {
+    /// The operation succeeded
+    Ok,
+    /// The passed pointer is null
+    NullPointer,
+    /// The operation is not supported by the platform or the windowing backend
+    NotSupported,
+    /// The operating system failed to perform the operation
+    OsError,
+    /// The value belongs to another thread
+    WrongThread,
+    /// The rendering context was lost and must be recreated
+    ContextLost,
+    /// An argument is out of range or has an unknown value
+    InvalidArgument,
+}
+
+impl From<Result<(), ExternalError>> for GlutinResult {
+    fn from(result: Result<(), ExternalError>) -> Self {
+        match result {
+            Ok(_) => GlutinResult::Ok,
+            Err(ExternalError::NotSupported(_)) => GlutinResult::NotSupported,
+            Err(ExternalError::Os(error)) => {
+                error!("{}", error);
+                GlutinResult::OsError
+            }
+        }
+    }
+}
+
+impl From<Result<(), ContextError>> for GlutinResult {
+    fn from(result: Result<(), ContextError>) -> Self {
+        match result {
+            Ok(_) => GlutinResult::Ok,
+            Err(ContextError::OsError(string)) => {
+                error!("OS Error in the rendering context: {}", string);
+                GlutinResult::OsError
+            }
+            Err(ContextError::IoError(error)) => {
+                error!("IO Error in the rendering context: {:?}", error);
+                GlutinResult::OsError
+            }
+            Err(ContextError::ContextLost) => {
+                error!("The rendering context is lost");
+                GlutinResult::ContextLost
+            }
+            Err(ContextError::FunctionUnavailable) => {
+                error!("The rendering context does not support the function");
+                GlutinResult::NotSupported
+            }
+        }
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 #[repr(u32)]
@@ -97,3 +156,62 @@ impl From<GlutinCursorIcon> for CursorIcon {
         }
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GlutinUserAttentionType {
+    /// Cancel a previous request for the user's attention.
+    None,
+    /// Platform-dependent, but typically bounces the dock icon until the
+    /// application is focused or flashes the taskbar icon.
+    Critical,
+    /// Platform-dependent, but typically bounces the dock icon once
+    /// or flashes the taskbar icon until the application is focused.
+    Informational,
+}
+
+impl From<GlutinUserAttentionType> for Option<UserAttentionType> {
+    fn from(attention_type: GlutinUserAttentionType) -> Option<UserAttentionType> {
+        match attention_type {
+            GlutinUserAttentionType::None => None,
+            GlutinUserAttentionType::Critical => Some(UserAttentionType::Critical),
+            GlutinUserAttentionType::Informational => Some(UserAttentionType::Informational),
+        }
+    }
+}
+
+/// X11 `_NET_WM_WINDOW_TYPE` hints, ignored on other platforms.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GlutinX11WindowType {
+    /// A normal, top-level window.
+    Normal,
+    /// A desktop feature. This can include a single window containing desktop icons with the same dimensions as the
+    /// screen, allowing the desktop environment to have full control of the desktop, without the need for proxying
+    /// root window clicks.
+    Desktop,
+    /// A dock or panel feature. Typically a Window Manager would keep such windows on top of all other windows.
+    Dock,
+    /// Toolbar windows. "Torn off" from the main application.
+    Toolbar,
+    /// Pinnable menu windows. "Torn off" from the main application.
+    Menu,
+    /// A small persistent utility window, such as a palette or toolbox.
+    Utility,
+    /// The window is a splash screen displayed as an application is starting up.
+    Splash,
+    /// This is a dialog window.
+    Dialog,
+    /// A dropdown menu that usually appears when the user clicks on an item in a menu bar.
+    DropdownMenu,
+    /// A popup menu that usually appears when the user right clicks on an object.
+    PopupMenu,
+    /// A tooltip window. Usually used to show additional information when hovering over an object with the cursor.
+    Tooltip,
+    /// The window is a notification.
+    Notification,
+    /// This should be used on the windows that are popped up by combo boxes.
+    Combo,
+    /// This indicates the the window is being dragged.
+    Dnd,
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use value_box::ValueBox;
+
+use crate::enums::GlutinResult;
+use crate::event_loop::GlutinEventLoop;
+use crate::events::{GlutinEvent, GlutinEventType};
+use crate::thread_guard::GlutinThreadGuardedPointer;
+
+/// Decides which translated events reach the host and merges bursts of cursor and wheel events.
+/// Coalesced events are held back until an event that can not be merged with them arrives,
+/// which at the latest is the `MainEventsCleared` at the end of the current batch of events.
+/// Key presses are held back the same way until the character they produce arrives, every
+/// platform reports it in a `ReceivedCharacter` right after the press.
+#[derive(Debug)]
+pub struct EventFilter {
+    /// A bit per `GlutinEventType`, only events with their bit set are sent to the host
+    pub mask: u64,
+    /// Merge consecutive cursor moves within a window into the latest position
+    pub coalesce_cursor_moves: bool,
+    /// Accumulate the deltas of consecutive mouse wheel events within a window
+    pub coalesce_mouse_wheel: bool,
+    /// A bit per `GlutinEventType` delivered even if the host did not subscribe to it,
+    /// used by runners that structure their output around these events
+    pub always_delivered: u64,
+    pending: Option<GlutinEvent>,
+    /// The logical characters of the pressed keys by their scan code, given to their releases
+    key_characters: HashMap<u32, u32>,
+}
+
+impl EventFilter {
+    pub fn new() -> Self {
+        Self {
+            mask: glutin_default_event_mask(),
+            coalesce_cursor_moves: false,
+            coalesce_mouse_wheel: false,
+            always_delivered: 0,
+            pending: None,
+            key_characters: HashMap::new(),
+        }
+    }
+
+    pub fn is_subscribed(&self, event_type: GlutinEventType) -> bool {
+        self.mask & glutin_event_type_bit(event_type) != 0
+    }
+
+    pub fn set_subscribed(&mut self, event_type: GlutinEventType, is_subscribed: bool) {
+        if is_subscribed {
+            self.mask |= glutin_event_type_bit(event_type);
+        } else {
+            self.mask &= !glutin_event_type_bit(event_type);
+        }
+    }
+
+    fn is_delivered(&self, event_type: GlutinEventType) -> bool {
+        (self.mask | self.always_delivered) & glutin_event_type_bit(event_type) != 0
+    }
+
+    fn can_coalesce(&self, event_type: GlutinEventType) -> bool {
+        match event_type {
+            GlutinEventType::WindowEventCursorMoved => self.coalesce_cursor_moves,
+            GlutinEventType::WindowEventMouseWheel => self.coalesce_mouse_wheel,
+            _ => false,
+        }
+    }
+
+    /// Deliver the held back coalesced event, if any, when no more events will follow it
+    pub fn flush(&mut self, mut deliver: impl FnMut(&mut GlutinEvent)) {
+        if let Some(mut pending) = self.pending.take() {
+            if let Some((scan_code, character)) = pending.key_logical_character() {
+                self.key_characters.insert(scan_code, character);
+            }
+            if self.is_subscribed(pending.event_type) {
+                deliver(&mut pending);
+            }
+        }
+    }
+
+    /// Drop the held back coalesced event, if any, when it can not be delivered anymore
+    pub fn discard_pending(&mut self) {
+        self.pending = None;
+    }
+
+    /// Pass a translated event through the filter, calling `deliver` for every event
+    /// that should be sent to the host
+    pub fn dispatch(
+        &mut self,
+        mut c_event: GlutinEvent,
+        mut deliver: impl FnMut(&mut GlutinEvent),
+    ) {
+        if let Some(pending) = self.pending.as_mut() {
+            if pending.coalesce(&c_event) {
+                return;
+            }
+            pending.attach_key_text(&c_event);
+        }
+
+        self.flush(&mut deliver);
+        c_event.set_released_key_character(&mut self.key_characters);
+
+        if !self.is_delivered(c_event.event_type) {
+            return;
+        }
+
+        if self.can_coalesce(c_event.event_type) || c_event.is_key_press_without_text() {
+            self.pending = Some(c_event);
+        } else {
+            deliver(&mut c_event);
+        }
+    }
+}
+
+/// All events except the markers of loop iterations that arrive on every wake up of the loop,
+/// hosts subscribe to them explicitly if they need them
+fn glutin_default_event_mask() -> u64 {
+    !(glutin_event_type_bit(GlutinEventType::NewEvents)
+        | glutin_event_type_bit(GlutinEventType::RedrawEventsCleared))
+}
+
+pub(crate) fn glutin_event_type_bit(event_type: GlutinEventType) -> u64 {
+    1u64.checked_shl(event_type as u32).unwrap_or(0)
+}
+
+/// Set which event types are sent to the host, a bit per `GlutinEventType` value.
+/// All events except `NewEvents` and `RedrawEventsCleared` are sent by default.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_set_event_mask(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    mask: u64,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.event_filter.mask = mask;
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_get_event_mask(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> u64 {
+    _ptr_events_loop.with_owned_return(0, |event_loop| event_loop.event_filter.mask)
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_set_event_subscribed(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    event_type: GlutinEventType,
+    is_subscribed: bool,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop
+            .event_filter
+            .set_subscribed(event_type, is_subscribed);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_is_event_subscribed(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    event_type: GlutinEventType,
+) -> bool {
+    _ptr_events_loop.with_owned_return(false, |event_loop| {
+        event_loop.event_filter.is_subscribed(event_type)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_set_coalesce_cursor_moves(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    coalesce: bool,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.event_filter.coalesce_cursor_moves = coalesce;
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_set_coalesce_mouse_wheel(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    coalesce: bool,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.event_filter.coalesce_mouse_wheel = coalesce;
+        GlutinResult::Ok
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use value_box::ValueBoxPointer;
+
+    use super::*;
+    use crate::event_loop::glutin_destroy_events_loop;
+    use crate::events::tests::{key_logical_character, key_press, key_release, received_character};
+    use crate::events::{GlutinControlFlow, GlutinEventLogicalKey};
+    use crate::mock::fixtures::*;
+    use crate::windowed_context::{
+        glutin_destroy_windowed_context, glutin_windowed_context_request_redraw,
+    };
+
+    #[test]
+    fn key_press_is_delivered_with_the_character_it_produced() {
+        let mut filter = EventFilter::new();
+        let mut delivered = vec![];
+
+        let events = [
+            key_press(None, GlutinEventLogicalKey::Unidentified),
+            received_character("a"),
+            key_release(None),
+            GlutinEvent {
+                event_type: GlutinEventType::MainEventsCleared,
+                ..Default::default()
+            },
+        ];
+        for c_event in events {
+            filter.dispatch(c_event, |c_event| {
+                delivered.push((
+                    c_event.event_type,
+                    c_event.text.string(),
+                    key_logical_character(c_event),
+                ))
+            });
+        }
+
+        let character = (GlutinEventLogicalKey::Character, 'a' as u32);
+        let none = (GlutinEventLogicalKey::Unidentified, 0);
+        let a = Some("a".to_string());
+        assert_eq!(
+            delivered,
+            vec![
+                (
+                    GlutinEventType::WindowEventKeyboardInput,
+                    a.clone(),
+                    character
+                ),
+                (GlutinEventType::WindowEventReceivedCharacter, a, none),
+                (GlutinEventType::WindowEventKeyboardInput, None, character),
+                (GlutinEventType::MainEventsCleared, None, none),
+            ]
+        );
+    }
+
+    #[test]
+    fn iteration_markers_are_not_sent_by_default() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        glutin_windowed_context_request_redraw(window);
+
+        let mut event_types = vec![];
+        event_loop.with_not_null(|event_loop| {
+            event_loop.pump_events(std::time::Duration::ZERO, |c_event| {
+                event_types.push(c_event.event_type);
+                GlutinControlFlow::Poll
+            });
+        });
+        assert!(event_types.contains(&GlutinEventType::MainEventsCleared));
+        assert!(!event_types.contains(&GlutinEventType::NewEvents));
+        assert!(!event_types.contains(&GlutinEventType::RedrawEventsCleared));
+
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+}
@@ -0,0 +1,419 @@
+use geometry_box::U128Box;
+use glutin::dpi::{PhysicalPosition, PhysicalSize};
+use glutin::event::{
+    DeviceId, ElementState, Event, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
+    Touch, TouchPhase, VirtualKeyCode, WindowEvent,
+};
+use glutin::window::WindowId;
+use value_box::{ValueBox, ValueBoxPointer};
+
+use crate::enums::GlutinResult;
+use crate::event_loop::GlutinEventLoop;
+use crate::events::{
+    glutin_virtual_keycode, EventProcessor, GlutinEvent, GlutinEventInputElementState,
+    GlutinEventMouseButtonType, GlutinEventMouseScrollDeltaType, GlutinEventTouchPhase,
+};
+use crate::thread_guard::GlutinThreadGuardedPointer;
+
+/// An event queued for delivery at the start of the next iteration of the loop
+#[derive(Debug)]
+pub enum GlutinQueuedEvent {
+    /// A window event injected by the host, translated when it is delivered
+    Injected {
+        window_id: U128Box,
+        event: WindowEvent<'static>,
+    },
+    /// An event translated by the library itself, such as an error
+    Translated(Box<GlutinEvent>),
+}
+
+impl GlutinQueuedEvent {
+    /// Translate the event in order with the events received from the windowing system.
+    /// Returns None if the event has no translation.
+    pub fn translate(
+        self,
+        event_processor: &mut EventProcessor,
+        wait_timeout: u64,
+    ) -> Option<GlutinEvent> {
+        match self {
+            GlutinQueuedEvent::Injected { window_id, event } => {
+                let mut c_event = GlutinEvent {
+                    wait_timeout,
+                    ..Default::default()
+                };
+                let event = Event::WindowEvent {
+                    window_id: unsafe { WindowId::dummy() },
+                    event,
+                };
+                if !event_processor.process_safely(event, &mut c_event) {
+                    return None;
+                }
+                c_event.window_id = window_id;
+                c_event.is_synthetic = true;
+                Some(c_event)
+            }
+            GlutinQueuedEvent::Translated(mut c_event) => {
+                c_event.wait_timeout = wait_timeout;
+                Some(*c_event)
+            }
+        }
+    }
+}
+
+/// Queue a window event for a window with a given id as if it was received from the windowing
+/// system. It is translated when it is delivered at the start of the next iteration of the loop,
+/// so it updates the modifiers state in order with the real events and is stamped with it.
+/// Delivered events are marked with `is_synthetic`.
+/// Returns `InvalidArgument` if the event could not be created from the arguments.
+fn glutin_events_loop_inject(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    event: Option<WindowEvent<'static>>,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        let event = match event {
+            None => return GlutinResult::InvalidArgument,
+            Some(event) => event,
+        };
+
+        _ptr_window_id.with_not_null_return(GlutinResult::NullPointer, |window_id| {
+            event_loop
+                .synthetic_events
+                .push_back(GlutinQueuedEvent::Injected {
+                    window_id: U128Box {
+                        low: window_id.low,
+                        high: window_id.high,
+                    },
+                    event,
+                });
+            GlutinResult::Ok
+        })
+    })
+}
+
+fn glutin_synthetic_device_id() -> DeviceId {
+    unsafe { DeviceId::dummy() }
+}
+
+fn glutin_element_state(state: GlutinEventInputElementState) -> Option<ElementState> {
+    match state {
+        GlutinEventInputElementState::Pressed => Some(ElementState::Pressed),
+        GlutinEventInputElementState::Released => Some(ElementState::Released),
+        GlutinEventInputElementState::Unknown => {
+            error!("Synthetic events must be either pressed or released");
+            None
+        }
+    }
+}
+
+/// The virtual keycode of an injected key, `Some(None)` if the key has no virtual keycode.
+/// Returns None if the value is out of range.
+fn glutin_injected_virtual_keycode(
+    has_virtual_keycode: bool,
+    value: u32,
+) -> Option<Option<VirtualKeyCode>> {
+    if !has_virtual_keycode {
+        return Some(None);
+    }
+    match glutin_virtual_keycode(value) {
+        Some(virtual_keycode) => Some(Some(virtual_keycode)),
+        None => {
+            error!("{} is not a virtual keycode", value);
+            None
+        }
+    }
+}
+
+fn glutin_touch_phase(phase: GlutinEventTouchPhase) -> Option<TouchPhase> {
+    match phase {
+        GlutinEventTouchPhase::Started => Some(TouchPhase::Started),
+        GlutinEventTouchPhase::Moved => Some(TouchPhase::Moved),
+        GlutinEventTouchPhase::Ended => Some(TouchPhase::Ended),
+        GlutinEventTouchPhase::Cancelled => Some(TouchPhase::Cancelled),
+        GlutinEventTouchPhase::Unknown => {
+            error!("Synthetic events must have a known touch phase");
+            None
+        }
+    }
+}
+
+fn glutin_mouse_button(
+    button_type: GlutinEventMouseButtonType,
+    button_code: u16,
+) -> Option<MouseButton> {
+    match button_type {
+        GlutinEventMouseButtonType::Left => Some(MouseButton::Left),
+        GlutinEventMouseButtonType::Right => Some(MouseButton::Right),
+        GlutinEventMouseButtonType::Middle => Some(MouseButton::Middle),
+        GlutinEventMouseButtonType::Other => Some(MouseButton::Other(button_code)),
+        GlutinEventMouseButtonType::Unknown => {
+            error!("Synthetic mouse events must have a known button");
+            None
+        }
+    }
+}
+
+/// Inject a key press or release. The virtual keycode is the value of winit's `VirtualKeyCode`
+/// and is ignored unless `has_virtual_keycode` is true.
+/// Returns `InvalidArgument` if the state is unknown or the virtual keycode is out of range.
+#[no_mangle]
+#[allow(deprecated)]
+pub extern "C" fn glutin_events_loop_inject_keyboard_input(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    scan_code: u32,
+    state: GlutinEventInputElementState,
+    has_virtual_keycode: bool,
+    virtual_keycode: u32,
+) -> GlutinResult {
+    let event = glutin_injected_virtual_keycode(has_virtual_keycode, virtual_keycode).and_then(
+        |virtual_keycode| {
+            glutin_element_state(state).map(|state| {
+                let input = KeyboardInput {
+                    scancode: scan_code,
+                    state,
+                    virtual_keycode,
+                    modifiers: ModifiersState::empty(),
+                };
+                WindowEvent::KeyboardInput {
+                    device_id: glutin_synthetic_device_id(),
+                    input,
+                    is_synthetic: false,
+                }
+            })
+        },
+    );
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, event)
+}
+
+/// Inject a received character given as a unicode code point.
+/// Returns `InvalidArgument` if the code point is not a valid character.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_inject_received_character(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    character: u32,
+) -> GlutinResult {
+    let event = match char::from_u32(character) {
+        None => {
+            error!(
+                "[glutin_events_loop_inject_received_character] {} is not a valid character",
+                character
+            );
+            None
+        }
+        Some(character) => Some(WindowEvent::ReceivedCharacter(character)),
+    };
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, event)
+}
+
+#[no_mangle]
+#[allow(deprecated)]
+pub extern "C" fn glutin_events_loop_inject_mouse_input(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    state: GlutinEventInputElementState,
+    button_type: GlutinEventMouseButtonType,
+    button_code: u16,
+) -> GlutinResult {
+    let event = glutin_element_state(state).and_then(|state| {
+        glutin_mouse_button(button_type, button_code).map(|button| WindowEvent::MouseInput {
+            device_id: glutin_synthetic_device_id(),
+            state,
+            button,
+            modifiers: ModifiersState::empty(),
+        })
+    });
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, event)
+}
+
+/// Inject a cursor move to a position in physical pixels relative to the top-left corner
+/// of the window
+#[no_mangle]
+#[allow(deprecated)]
+pub extern "C" fn glutin_events_loop_inject_cursor_moved(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    x: f64,
+    y: f64,
+) -> GlutinResult {
+    let event = WindowEvent::CursorMoved {
+        device_id: glutin_synthetic_device_id(),
+        position: PhysicalPosition::new(x, y),
+        modifiers: ModifiersState::empty(),
+    };
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, Some(event))
+}
+
+/// Inject a scroll by lines or by physical pixels
+#[no_mangle]
+#[allow(deprecated)]
+pub extern "C" fn glutin_events_loop_inject_mouse_wheel(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    delta_type: GlutinEventMouseScrollDeltaType,
+    x: f64,
+    y: f64,
+    phase: GlutinEventTouchPhase,
+) -> GlutinResult {
+    let delta = match delta_type {
+        GlutinEventMouseScrollDeltaType::LineDelta => {
+            Some(MouseScrollDelta::LineDelta(x as f32, y as f32))
+        }
+        GlutinEventMouseScrollDeltaType::PixelDelta => {
+            Some(MouseScrollDelta::PixelDelta(PhysicalPosition::new(x, y)))
+        }
+        GlutinEventMouseScrollDeltaType::Unknown => {
+            error!("[glutin_events_loop_inject_mouse_wheel] Unknown scroll delta type");
+            None
+        }
+    };
+
+    let event = delta.and_then(|delta| {
+        glutin_touch_phase(phase).map(|phase| WindowEvent::MouseWheel {
+            device_id: glutin_synthetic_device_id(),
+            delta,
+            phase,
+            modifiers: ModifiersState::empty(),
+        })
+    });
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, event)
+}
+
+/// Inject a touch of a finger with a given id, without force information
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_inject_touch(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    phase: GlutinEventTouchPhase,
+    x: f64,
+    y: f64,
+    id: u64,
+) -> GlutinResult {
+    let event = glutin_touch_phase(phase).map(|phase| {
+        WindowEvent::Touch(Touch {
+            device_id: glutin_synthetic_device_id(),
+            phase,
+            location: PhysicalPosition::new(x, y),
+            force: None,
+            id,
+        })
+    });
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, event)
+}
+
+/// Inject a resize of the window to a given physical size.
+/// Only the event is injected, the actual window is not resized.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_inject_resized(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+    width: u32,
+    height: u32,
+) -> GlutinResult {
+    let event = WindowEvent::Resized(PhysicalSize::new(width, height));
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, Some(event))
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_inject_close_requested(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_window_id: *mut ValueBox<U128Box>,
+) -> GlutinResult {
+    let event = WindowEvent::CloseRequested;
+    glutin_events_loop_inject(_ptr_events_loop, _ptr_window_id, Some(event))
+}
+
+/// Return the amount of injected events that have not been delivered yet
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_get_synthetic_events_count(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> usize {
+    _ptr_events_loop.with_owned_return(0, |event_loop| event_loop.synthetic_events.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_loop::glutin_destroy_events_loop;
+    use crate::mock::fixtures::*;
+    use crate::windowed_context::glutin_destroy_windowed_context;
+
+    #[test]
+    fn injection_results_tell_apart_pointers_threads_and_arguments() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let id = window_id(window);
+        let pressed = GlutinEventInputElementState::Pressed;
+        let key_a = VirtualKeyCode::A as u32;
+
+        let cases = [
+            (
+                glutin_events_loop_inject_keyboard_input(event_loop, id, 30, pressed, true, key_a),
+                GlutinResult::Ok,
+            ),
+            (
+                glutin_events_loop_inject_keyboard_input(event_loop, id, 30, pressed, false, 9999),
+                GlutinResult::Ok,
+            ),
+            (
+                glutin_events_loop_inject_keyboard_input(event_loop, id, 30, pressed, true, 9999),
+                GlutinResult::InvalidArgument,
+            ),
+            (
+                glutin_events_loop_inject_keyboard_input(
+                    event_loop,
+                    id,
+                    30,
+                    GlutinEventInputElementState::Unknown,
+                    true,
+                    key_a,
+                ),
+                GlutinResult::InvalidArgument,
+            ),
+            (
+                glutin_events_loop_inject_received_character(event_loop, id, 0xd800),
+                GlutinResult::InvalidArgument,
+            ),
+            (
+                glutin_events_loop_inject_close_requested(std::ptr::null_mut(), id),
+                GlutinResult::NullPointer,
+            ),
+            (
+                glutin_events_loop_inject_keyboard_input(
+                    std::ptr::null_mut(),
+                    id,
+                    30,
+                    pressed,
+                    true,
+                    9999,
+                ),
+                GlutinResult::NullPointer,
+            ),
+            (
+                glutin_events_loop_inject_close_requested(event_loop, std::ptr::null_mut()),
+                GlutinResult::NullPointer,
+            ),
+        ];
+        for (index, (result, expected)) in cases.into_iter().enumerate() {
+            assert_eq!(result, expected, "case {}", index);
+        }
+        assert_eq!(glutin_events_loop_get_synthetic_events_count(event_loop), 2);
+
+        // raw pointers are not Send, the test passes them as addresses
+        let (thread_event_loop, thread_id) = (event_loop as usize, id as usize);
+        let result = std::thread::spawn(move || {
+            glutin_events_loop_inject_close_requested(
+                thread_event_loop as *mut ValueBox<GlutinEventLoop>,
+                thread_id as *mut ValueBox<U128Box>,
+            )
+        })
+        .join()
+        .unwrap();
+        assert_eq!(result, GlutinResult::WrongThread);
+
+        id.release();
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+}
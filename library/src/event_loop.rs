@@ -1,16 +1,377 @@
-use crate::events::{EventProcessor, GlutinControlFlow, GlutinEvent};
+use crate::command_queue::GlutinCommandQueue;
+use crate::enums::GlutinResult;
+use crate::event_filter::EventFilter;
+use crate::event_injection::GlutinQueuedEvent;
+use crate::event_recording::EventRecorder;
+use crate::events::{glutin_panic_message, EventProcessor, GlutinControlFlow, GlutinEvent};
+use crate::mock::MockEventLoop;
+use crate::thread_guard::GlutinThreadGuardedPointer;
+use geometry_box::U128Box;
+use glutin::event::Event;
 use glutin::event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget};
 use glutin::monitor::MonitorHandle;
 use glutin::platform::run_return::EventLoopExtRunReturn;
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::time;
 use value_box::{ValueBox, ValueBoxPointer};
 
 pub type GlutinCustomEvent = u32;
-pub type GlutinEventLoop = EventLoop<GlutinCustomEvent>;
-pub type GlutinEventLoopProxy = EventLoopProxy<GlutinCustomEvent>;
+pub type GlutinEventLoopProxy = EventLoopProxy<GlutinLoopEvent>;
+
+/// The user event of a native event loop
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlutinLoopEvent {
+    /// An event sent by the host, delivered to it as a `UserEvent`
+    Custom(GlutinCustomEvent),
+    /// Wakes up the loop to perform the queued window commands, it is never sent to the host
+    CommandsQueued,
+}
+
+/// The windowing backend behind an event loop
+pub enum GlutinEventLoopBackend {
+    /// The native windowing system of the platform
+    Native(EventLoop<GlutinLoopEvent>),
+    /// In-memory windows that need neither a display server nor a GPU, for testing
+    Mock(MockEventLoop),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum GlutinBackendType {
+    Native,
+    Mock,
+}
+
+impl Default for GlutinBackendType {
+    fn default() -> Self {
+        GlutinBackendType::Native
+    }
+}
+
+/// An event loop together with the options of how its events are translated
+/// and delivered to the host
+pub struct GlutinEventLoop {
+    pub backend: GlutinEventLoopBackend,
+    /// Translates events and keeps the keyboard state between runs of the loop
+    pub event_processor: EventProcessor,
+    /// Filters and coalesces translated events before they are sent to the host
+    pub event_filter: EventFilter,
+    /// Records translated events while a recording is in progress
+    pub event_recorder: Option<EventRecorder>,
+    /// Events injected by the host or queued by the library, delivered after the next `NewEvents`
+    pub synthetic_events: VecDeque<GlutinQueuedEvent>,
+    /// Pumped events that did not fit into the array of the host,
+    /// delivered first by the next `glutin_events_loop_pump_events_into`
+    pub overflow_events: VecDeque<GlutinEvent>,
+    /// How long to wait for new events when the callback returns `WaitUntil`
+    /// without changing the timeout of the event
+    pub wait_timeout: time::Duration,
+    /// Window operations requested by other threads
+    pub commands: GlutinCommandQueue,
+}
+
+impl GlutinEventLoop {
+    pub fn new() -> Self {
+        Self::with_backend(GlutinBackendType::Native)
+    }
+
+    pub fn with_backend(backend_type: GlutinBackendType) -> Self {
+        let backend = match backend_type {
+            GlutinBackendType::Native => {
+                GlutinEventLoopBackend::Native(EventLoop::with_user_event())
+            }
+            GlutinBackendType::Mock => GlutinEventLoopBackend::Mock(MockEventLoop::new()),
+        };
+        // the mock loop never waits, so it does not need to be woken up
+        let commands = GlutinCommandQueue::new(match &backend {
+            GlutinEventLoopBackend::Native(event_loop) => Some(event_loop.create_proxy()),
+            GlutinEventLoopBackend::Mock(_) => None,
+        });
+        let mut event_processor = EventProcessor::new();
+        if let GlutinEventLoopBackend::Native(event_loop) = &backend {
+            event_processor.lock_keys_query = crate::ext::glutin_lock_keys_query(event_loop);
+            event_processor.logical_character_query =
+                crate::ext::glutin_logical_character_query(event_loop);
+        }
+
+        Self {
+            backend,
+            event_processor,
+            event_filter: EventFilter::new(),
+            event_recorder: None,
+            synthetic_events: VecDeque::new(),
+            overflow_events: VecDeque::new(),
+            wait_timeout: time::Duration::from_millis(50),
+            commands,
+        }
+    }
+
+    pub fn backend_type(&self) -> GlutinBackendType {
+        match self.backend {
+            GlutinEventLoopBackend::Native(_) => GlutinBackendType::Native,
+            GlutinEventLoopBackend::Mock(_) => GlutinBackendType::Mock,
+        }
+    }
+
+    /// The native event loop, or None for the mock backend
+    pub fn native(&self) -> Option<&EventLoop<GlutinLoopEvent>> {
+        match &self.backend {
+            GlutinEventLoopBackend::Native(event_loop) => Some(event_loop),
+            GlutinEventLoopBackend::Mock(_) => None,
+        }
+    }
+
+    /// Run the event loop until the callback returns `Exit`
+    pub fn run_events(&mut self, mut callback: impl FnMut(&mut GlutinEvent) -> GlutinControlFlow) {
+        let Self {
+            backend,
+            event_processor,
+            event_filter,
+            event_recorder,
+            synthetic_events,
+            wait_timeout,
+            commands,
+            ..
+        } = self;
+        let wait_timeout = wait_timeout.as_micros() as u64;
+
+        let mut handle_event = |event: Event<'_, GlutinLoopEvent>,
+                                window_id: Option<U128Box>,
+                                control_flow: &mut ControlFlow| {
+            if let Event::UserEvent(GlutinLoopEvent::CommandsQueued) = event {
+                commands.perform();
+                return;
+            }
+
+            let is_iteration_start = matches!(event, Event::NewEvents(_));
+            let mut dispatch = |c_event: GlutinEvent, control_flow: &mut ControlFlow| {
+                glutin_record_event(event_recorder, &c_event);
+                event_filter.dispatch(c_event, |c_event| {
+                    let c_control_flow = callback(c_event);
+                    *control_flow = glutin_control_flow(c_control_flow, c_event.wait_timeout);
+                });
+            };
+
+            // keep the control flow between events until the host asks to change it
+            let mut c_event: GlutinEvent = Default::default();
+            c_event.wait_timeout = wait_timeout;
+            let processed = event_processor.process_safely(event, &mut c_event);
+            if processed {
+                if let Some(window_id) = window_id {
+                    c_event.window_id = window_id;
+                }
+                dispatch(c_event, control_flow);
+            }
+
+            if is_iteration_start {
+                commands.perform();
+                while let Some(queued_event) = synthetic_events.pop_front() {
+                    if let Some(c_event) = queued_event.translate(event_processor, wait_timeout) {
+                        dispatch(c_event, control_flow);
+                    }
+                }
+            }
+
+            // do not wait for real events while injected events or commands are queued
+            if (!synthetic_events.is_empty() || !commands.is_empty())
+                && matches!(*control_flow, ControlFlow::Wait | ControlFlow::WaitUntil(_))
+            {
+                *control_flow = ControlFlow::Poll;
+            }
+        };
+
+        let mut panic_message = None;
+        match backend {
+            GlutinEventLoopBackend::Native(event_loop) => event_loop.run_return(
+                |event,
+                 _events_loop: &EventLoopWindowTarget<GlutinLoopEvent>,
+                 control_flow: &mut ControlFlow| {
+                    glutin_handle_event_safely(&mut panic_message, control_flow, |control_flow| {
+                        handle_event(event, None, control_flow)
+                    })
+                },
+            ),
+            GlutinEventLoopBackend::Mock(event_loop) => {
+                event_loop.run_return(|event, window_id, control_flow| {
+                    glutin_handle_event_safely(&mut panic_message, control_flow, |control_flow| {
+                        handle_event(event, window_id, control_flow)
+                    })
+                })
+            }
+        }
+        // a loop that exits on a panic may still hold back a coalesced event
+        event_filter.discard_pending();
+        if let Some(message) = panic_message {
+            synthetic_events.push_back(GlutinQueuedEvent::Translated(Box::new(
+                GlutinEvent::error(message, wait_timeout),
+            )));
+        }
+    }
+
+    /// Dispatch all pending events and return, waiting at most `timeout` for new events
+    /// if there are none. Returns true if the callback asked to exit.
+    pub fn pump_events(
+        &mut self,
+        timeout: time::Duration,
+        mut callback: impl FnMut(&mut GlutinEvent) -> GlutinControlFlow,
+    ) -> bool {
+        let Self {
+            backend,
+            event_processor,
+            event_filter,
+            event_recorder,
+            synthetic_events,
+            wait_timeout,
+            commands,
+            ..
+        } = self;
+        let wait_timeout = wait_timeout.as_micros() as u64;
+        // a timeout too large to be represented as an instant waits without a deadline
+        let deadline = time::Instant::now().checked_add(timeout);
+        let mut has_events = false;
+        let mut is_exit_requested = false;
+
+        let mut handle_event = |event: Event<'_, GlutinLoopEvent>,
+                                window_id: Option<U128Box>,
+                                control_flow: &mut ControlFlow| {
+            let is_iteration_end = matches!(event, Event::RedrawEventsCleared);
+            let is_input = matches!(
+                event,
+                Event::WindowEvent { .. }
+                    | Event::DeviceEvent { .. }
+                    | Event::UserEvent(_)
+                    | Event::RedrawRequested(_)
+            );
+
+            // pumping stops the loop by exiting it, but the loop itself is not destroyed
+            if let Event::LoopDestroyed = event {
+                event_filter.flush(|c_event| {
+                    if let GlutinControlFlow::Exit = callback(c_event) {
+                        is_exit_requested = true;
+                    }
+                });
+                return;
+            }
+
+            if let Event::UserEvent(GlutinLoopEvent::CommandsQueued) = event {
+                commands.perform();
+                return;
+            }
+
+            let is_iteration_start = matches!(event, Event::NewEvents(_));
+            let mut dispatch = |c_event: GlutinEvent| {
+                glutin_record_event(event_recorder, &c_event);
+                event_filter.dispatch(c_event, |c_event| {
+                    if let GlutinControlFlow::Exit = callback(c_event) {
+                        is_exit_requested = true;
+                    }
+                });
+            };
+
+            let mut c_event: GlutinEvent = Default::default();
+            c_event.wait_timeout = wait_timeout;
+            if event_processor.process_safely(event, &mut c_event) {
+                has_events |= is_input;
+                if let Some(window_id) = window_id {
+                    c_event.window_id = window_id;
+                }
+                dispatch(c_event);
+            }
+
+            if is_iteration_start {
+                commands.perform();
+                while let Some(queued_event) = synthetic_events.pop_front() {
+                    has_events = true;
+                    if let Some(c_event) = queued_event.translate(event_processor, wait_timeout) {
+                        dispatch(c_event);
+                    }
+                }
+            }
+
+            if is_exit_requested {
+                *control_flow = ControlFlow::Exit;
+            } else if is_iteration_end {
+                *control_flow = if has_events
+                    || !synthetic_events.is_empty()
+                    || !commands.is_empty()
+                    || matches!(deadline, Some(deadline) if time::Instant::now() >= deadline)
+                {
+                    ControlFlow::Exit
+                } else {
+                    deadline.map_or(ControlFlow::Wait, ControlFlow::WaitUntil)
+                };
+            }
+        };
+
+        let mut panic_message = None;
+        match backend {
+            GlutinEventLoopBackend::Native(event_loop) => event_loop.run_return(
+                |event,
+                 _events_loop: &EventLoopWindowTarget<GlutinLoopEvent>,
+                 control_flow: &mut ControlFlow| {
+                    glutin_handle_event_safely(&mut panic_message, control_flow, |control_flow| {
+                        handle_event(event, None, control_flow)
+                    })
+                },
+            ),
+            GlutinEventLoopBackend::Mock(event_loop) => {
+                event_loop.run_return(|event, window_id, control_flow| {
+                    glutin_handle_event_safely(&mut panic_message, control_flow, |control_flow| {
+                        handle_event(event, window_id, control_flow)
+                    })
+                })
+            }
+        }
+        let has_panicked = panic_message.is_some();
+        // a loop that exits on a panic may still hold back a coalesced event
+        event_filter.discard_pending();
+        if let Some(message) = panic_message {
+            synthetic_events.push_back(GlutinQueuedEvent::Translated(Box::new(
+                GlutinEvent::error(message, wait_timeout),
+            )));
+        }
+
+        is_exit_requested || has_panicked
+    }
+}
+
+/// Handle an event of a running loop without unwinding into the windowing system or the host.
+/// A panic exits the loop and its message is kept to be delivered as an `Error` event
+/// by the next run, later events of the exiting loop are ignored.
+fn glutin_handle_event_safely(
+    panic_message: &mut Option<String>,
+    control_flow: &mut ControlFlow,
+    handle_event: impl FnOnce(&mut ControlFlow),
+) {
+    if panic_message.is_some() {
+        *control_flow = ControlFlow::Exit;
+        return;
+    }
+    if let Err(panic) = catch_unwind(AssertUnwindSafe(|| handle_event(control_flow))) {
+        let message = glutin_panic_message(panic.as_ref());
+        error!("Failed to handle an event, exiting the loop: {}", message);
+        *panic_message = Some(message);
+        *control_flow = ControlFlow::Exit;
+    }
+}
+
+/// Create an event loop with the native backend, or with the mock backend
+/// if the `GLUTIN_BACKEND` environment variable is set to `mock`
+#[no_mangle]
+pub extern "C" fn glutin_create_events_loop() -> *mut ValueBox<GlutinEventLoop> {
+    let backend_type = match std::env::var("GLUTIN_BACKEND") {
+        Ok(backend) if backend.eq_ignore_ascii_case("mock") => GlutinBackendType::Mock,
+        _ => GlutinBackendType::Native,
+    };
+    glutin_create_events_loop_with_backend(backend_type)
+}
 
 #[no_mangle]
-pub fn glutin_create_events_loop() -> *mut ValueBox<GlutinEventLoop> {
+pub extern "C" fn glutin_create_events_loop_with_backend(
+    backend_type: GlutinBackendType,
+) -> *mut ValueBox<GlutinEventLoop> {
     #[cfg(target_os = "linux")]
     {
         // respect the winit backend if it is set
@@ -18,51 +379,211 @@ pub fn glutin_create_events_loop() -> *mut ValueBox<GlutinEventLoop> {
             std::env::set_var("WINIT_UNIX_BACKEND", "x11");
         }
     }
-    ValueBox::new(GlutinEventLoop::with_user_event()).into_raw()
+    ValueBox::new(GlutinEventLoop::with_backend(backend_type))
+        .into_raw()
+        .owned_by_current_thread()
 }
 
 #[no_mangle]
-pub fn glutin_destroy_events_loop(_ptr: *mut ValueBox<GlutinEventLoop>) {
-    _ptr.release();
+pub extern "C" fn glutin_events_loop_get_backend_type(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> GlutinBackendType {
+    _ptr_events_loop.with_owned_return(GlutinBackendType::Native, |event_loop| {
+        event_loop.backend_type()
+    })
+}
+
+/// Destroy the event loop. Returns `WrongThread` and leaks the event loop if called
+/// by another thread than the one that created it.
+#[no_mangle]
+pub extern "C" fn glutin_destroy_events_loop(_ptr: *mut ValueBox<GlutinEventLoop>) -> GlutinResult {
+    _ptr.release_owned()
 }
 
 #[no_mangle]
-pub fn glutin_events_loop_run_return(
+pub extern "C" fn glutin_events_loop_run_return(
     _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
     callback: extern "C" fn(*mut GlutinEvent) -> GlutinControlFlow,
-) {
-    if _ptr_events_loop.is_null() {
-        eprintln!("[glutin_events_loop_run_return] _ptr_events_loop is null");
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.run_events(|c_event| callback(c_event));
+        GlutinResult::Ok
+    })
+}
+
+/// Run the event loop like `glutin_events_loop_run_return`, passing the user data
+/// back to the callback together with every event
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_run_return_with_data(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    callback: extern "C" fn(*mut c_void, *mut GlutinEvent) -> GlutinControlFlow,
+    user_data: *mut c_void,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.run_events(|c_event| callback(user_data, c_event));
+        GlutinResult::Ok
+    })
+}
+
+/// Dispatch all pending events to the callback and return, waiting at most `timeout` microseconds
+/// for new events if there are none. Lets the host drive the event loop from its own main loop.
+/// Returns `Exit` if the callback asked to exit, `Poll` otherwise.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_pump_events(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    timeout: u64,
+    callback: extern "C" fn(*mut GlutinEvent) -> GlutinControlFlow,
+) -> GlutinControlFlow {
+    _ptr_events_loop.with_owned_return(GlutinControlFlow::Exit, |event_loop| {
+        let is_exit_requested = event_loop
+            .pump_events(time::Duration::from_micros(timeout), |c_event| {
+                callback(c_event)
+            });
+        if is_exit_requested {
+            GlutinControlFlow::Exit
+        } else {
+            GlutinControlFlow::Poll
+        }
+    })
+}
+
+/// Dispatch pending events into a caller-provided array of `capacity` events and return
+/// the amount of written events, waiting at most `timeout` microseconds for new events
+/// if there are none.
+/// Pumping stops as soon as the array is full, events that do not fit stay queued and are
+/// written first by the next call, see `glutin_events_loop_get_overflow_events_count`.
+/// Written events own their text payload, release them with `glutin_events_release`.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_pump_events_into(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    timeout: u64,
+    events: *mut GlutinEvent,
+    capacity: usize,
+) -> usize {
+    if events.is_null() {
+        error!("[glutin_events_loop_pump_events_into] events are null");
+        return 0;
+    }
+
+    _ptr_events_loop.with_owned_return(0, |event_loop| {
+        let mut length = 0;
+        while length < capacity {
+            match event_loop.overflow_events.pop_front() {
+                Some(c_event) => {
+                    unsafe { std::ptr::write(events.add(length), c_event) };
+                    length += 1;
+                }
+                None => break,
+            }
+        }
+        // queued events are delivered before new ones are pumped
+        if length == capacity || !event_loop.overflow_events.is_empty() {
+            return length;
+        }
+
+        let mut overflow_events = VecDeque::new();
+        event_loop.pump_events(time::Duration::from_micros(timeout), |c_event| {
+            if length < capacity {
+                unsafe { std::ptr::write(events.add(length), std::mem::take(c_event)) };
+                length += 1;
+            } else {
+                overflow_events.push_back(std::mem::take(c_event));
+            }
+            if length < capacity {
+                GlutinControlFlow::Poll
+            } else {
+                GlutinControlFlow::Exit
+            }
+        });
+        event_loop.overflow_events = overflow_events;
+        length
+    })
+}
+
+/// Return how many pumped events did not fit into the array of the host and wait for the
+/// next `glutin_events_loop_pump_events_into`
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_get_overflow_events_count(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> usize {
+    _ptr_events_loop.with_owned_return(0, |event_loop| event_loop.overflow_events.len())
+}
+
+/// Release the payloads of events written by `glutin_events_loop_pump_events_into`.
+/// The array itself is owned by the caller.
+#[no_mangle]
+pub extern "C" fn glutin_events_release(events: *mut GlutinEvent, length: usize) {
+    if events.is_null() {
         return;
     }
+    for index in 0..length {
+        unsafe { std::ptr::drop_in_place(events.add(index)) };
+    }
+}
 
-    let mut event_processor = EventProcessor::new();
+fn glutin_record_event(event_recorder: &mut Option<EventRecorder>, c_event: &GlutinEvent) {
+    if let Some(recorder) = event_recorder {
+        if let Err(error) = recorder.record(c_event) {
+            error!(
+                "Failed to record an event, stopping the recording: {}",
+                error
+            );
+            *event_recorder = None;
+        }
+    }
+}
 
-    _ptr_events_loop.with_not_null(|event_loop| {
-        event_loop.run_return(
-            |event,
-             _events_loop: &EventLoopWindowTarget<GlutinCustomEvent>,
-             control_flow: &mut ControlFlow| {
-                *control_flow = ControlFlow::Poll;
-                let mut c_event: GlutinEvent = Default::default();
-                let processed = event_processor.process(event, &mut c_event);
-                if processed {
-                    let c_event_ptr = Box::into_raw(Box::new(c_event));
-                    let c_control_flow = callback(c_event_ptr);
-                    unsafe { Box::from_raw(c_event_ptr) };
-                    match c_control_flow {
-                        GlutinControlFlow::Poll => *control_flow = ControlFlow::Poll,
-                        GlutinControlFlow::Wait => {
-                            *control_flow = ControlFlow::WaitUntil(
-                                time::Instant::now() + time::Duration::new(0, 50 * 1000000),
-                            )
-                        }
-                        GlutinControlFlow::Exit => *control_flow = ControlFlow::Exit,
-                    }
-                }
-            },
-        );
-    });
+fn glutin_control_flow(c_control_flow: GlutinControlFlow, wait_timeout: u64) -> ControlFlow {
+    match c_control_flow {
+        GlutinControlFlow::Poll => ControlFlow::Poll,
+        GlutinControlFlow::Wait => ControlFlow::Wait,
+        GlutinControlFlow::WaitUntil => time::Instant::now()
+            .checked_add(time::Duration::from_micros(wait_timeout))
+            .map_or(ControlFlow::Wait, ControlFlow::WaitUntil),
+        GlutinControlFlow::Exit => ControlFlow::Exit,
+    }
+}
+
+/// Set the default timeout in microseconds used when the callback returns `WaitUntil`.
+/// The default timeout is 50ms.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_set_wait_timeout(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    wait_timeout: u64,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.wait_timeout = time::Duration::from_micros(wait_timeout);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_get_wait_timeout(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> u64 {
+    _ptr_events_loop.with_owned_return(0, |event_loop| event_loop.wait_timeout.as_micros() as u64)
+}
+
+/// Enable or disable translation of raw device events such as unaccelerated mouse motion.
+/// Device events are disabled by default.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_set_device_events_enabled(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    enabled: bool,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        event_loop.event_processor.device_events_enabled = enabled;
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_is_device_events_enabled(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> bool {
+    _ptr_events_loop.with_owned_return(false, |event_loop| {
+        event_loop.event_processor.device_events_enabled
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -73,10 +594,11 @@ pub enum GlutinEventLoopType {
     X11,
     Wayland,
     Unknown,
+    Mock,
 }
 
 #[cfg(target_os = "linux")]
-fn get_event_loop_type(_event_loop: &GlutinEventLoop) -> GlutinEventLoopType {
+fn get_event_loop_type(_event_loop: &EventLoop<GlutinLoopEvent>) -> GlutinEventLoopType {
     use glutin::platform::unix::EventLoopWindowTargetExtUnix;
     if _event_loop.is_wayland() {
         return GlutinEventLoopType::Wayland;
@@ -88,40 +610,46 @@ fn get_event_loop_type(_event_loop: &GlutinEventLoop) -> GlutinEventLoopType {
 }
 
 #[cfg(target_os = "windows")]
-fn get_event_loop_type(_event_loop: &GlutinEventLoop) -> GlutinEventLoopType {
+fn get_event_loop_type(_event_loop: &EventLoop<GlutinLoopEvent>) -> GlutinEventLoopType {
     GlutinEventLoopType::Windows
 }
 
 #[cfg(target_os = "macos")]
-fn get_event_loop_type(_event_loop: &GlutinEventLoop) -> GlutinEventLoopType {
+fn get_event_loop_type(_event_loop: &EventLoop<GlutinLoopEvent>) -> GlutinEventLoopType {
     GlutinEventLoopType::MacOS
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-fn get_event_loop_type(_event_loop: &GlutinEventLoop) -> GlutinEventLoopType {
+fn get_event_loop_type(_event_loop: &EventLoop<GlutinLoopEvent>) -> GlutinEventLoopType {
     GlutinEventLoopType::Unknown
 }
 
 #[no_mangle]
-fn glutin_events_loop_get_type(
+pub extern "C" fn glutin_events_loop_get_type(
     _ptr_event_loop: *mut ValueBox<GlutinEventLoop>,
 ) -> GlutinEventLoopType {
-    _ptr_event_loop.with_not_null_return(GlutinEventLoopType::Unknown, |event_loop| {
-        get_event_loop_type(event_loop)
+    _ptr_event_loop.with_owned_return(GlutinEventLoopType::Unknown, |event_loop| match event_loop
+        .native()
+    {
+        None => GlutinEventLoopType::Mock,
+        Some(event_loop) => get_event_loop_type(event_loop),
     })
 }
 
 #[no_mangle]
-fn glutin_events_loop_create_proxy(
+pub extern "C" fn glutin_events_loop_create_proxy(
     _ptr_event_loop: *mut ValueBox<GlutinEventLoop>,
 ) -> *mut ValueBox<GlutinEventLoopProxy> {
-    _ptr_event_loop.with_not_null_return(std::ptr::null_mut(), |event_loop| {
-        ValueBox::new(event_loop.create_proxy()).into_raw()
+    _ptr_event_loop.with_owned_return(std::ptr::null_mut(), |event_loop| {
+        match event_loop.native() {
+            None => std::ptr::null_mut(),
+            Some(event_loop) => ValueBox::new(event_loop.create_proxy()).into_raw(),
+        }
     })
 }
 
 #[no_mangle]
-fn glutin_events_loop_drop_proxy(_ptr: *mut ValueBox<GlutinEventLoopProxy>) {
+pub extern "C" fn glutin_events_loop_drop_proxy(_ptr: *mut ValueBox<GlutinEventLoopProxy>) {
     _ptr.release();
 }
 
@@ -130,11 +658,14 @@ fn glutin_events_loop_drop_proxy(_ptr: *mut ValueBox<GlutinEventLoopProxy>) {
 ///////////////////////////////////////////////////////////////////////////////////////
 
 #[no_mangle]
-fn glutin_events_loop_get_primary_monitor(
+pub extern "C" fn glutin_events_loop_get_primary_monitor(
     _ptr_event_loop: *mut ValueBox<GlutinEventLoop>,
 ) -> *mut ValueBox<MonitorHandle> {
-    _ptr_event_loop.with_not_null_return(std::ptr::null_mut(), |event_loop| {
-        match event_loop.primary_monitor() {
+    _ptr_event_loop.with_owned_return(std::ptr::null_mut(), |event_loop| {
+        match event_loop
+            .native()
+            .and_then(|event_loop| event_loop.primary_monitor())
+        {
             None => std::ptr::null_mut(),
             Some(monitor) => ValueBox::new(monitor).into_raw(),
         }
@@ -142,11 +673,123 @@ fn glutin_events_loop_get_primary_monitor(
 }
 
 #[no_mangle]
-fn glutin_primary_monitor_free(_ptr_monitor_id: *mut ValueBox<MonitorHandle>) {
+pub extern "C" fn glutin_primary_monitor_free(_ptr_monitor_id: *mut ValueBox<MonitorHandle>) {
     _ptr_monitor_id.release();
 }
 
 #[no_mangle]
-fn glutin_primary_monitor_get_hidpi_factor(_ptr_monitor_id: *mut ValueBox<MonitorHandle>) -> f64 {
+pub extern "C" fn glutin_primary_monitor_get_hidpi_factor(
+    _ptr_monitor_id: *mut ValueBox<MonitorHandle>,
+) -> f64 {
     _ptr_monitor_id.with_not_null_return(1.0, |monitor_id| monitor_id.scale_factor())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_injection::{
+        glutin_events_loop_get_synthetic_events_count, glutin_events_loop_inject_close_requested,
+        glutin_events_loop_inject_cursor_moved,
+    };
+    use crate::events::GlutinEventType;
+    use crate::mock::fixtures::*;
+    use crate::windowed_context::{
+        glutin_destroy_windowed_context, glutin_windowed_context_request_redraw,
+    };
+
+    #[test]
+    fn pumping_with_an_unbounded_timeout_does_not_overflow() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        glutin_windowed_context_request_redraw(window);
+
+        event_loop.with_not_null(|event_loop| {
+            event_loop.pump_events(std::time::Duration::MAX, |_| GlutinControlFlow::WaitUntil);
+        });
+
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn waiting_without_events_exits_the_loop() {
+        let event_loop = create_mock_event_loop();
+
+        let mut is_destroyed = false;
+        event_loop.with_not_null(|event_loop| {
+            event_loop.run_events(|c_event| {
+                is_destroyed |= c_event.event_type == GlutinEventType::LoopDestroyed;
+                GlutinControlFlow::Wait
+            })
+        });
+        assert!(is_destroyed);
+
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn events_that_do_not_fit_are_pumped_by_the_next_call() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let id = window_id(window);
+        for x in 0..3 {
+            assert_eq!(
+                glutin_events_loop_inject_cursor_moved(event_loop, id, x as f64, 0.0),
+                GlutinResult::Ok
+            );
+        }
+
+        let mut events = vec![GlutinEvent::default()];
+        assert_eq!(
+            glutin_events_loop_pump_events_into(event_loop, 0, events.as_mut_ptr(), 0),
+            0
+        );
+        assert_eq!(glutin_events_loop_get_synthetic_events_count(event_loop), 3);
+
+        let mut cursor_moves = 0;
+        for _ in 0..10 {
+            let length = glutin_events_loop_pump_events_into(event_loop, 0, events.as_mut_ptr(), 1);
+            if length == 1 && events[0].event_type == GlutinEventType::WindowEventCursorMoved {
+                cursor_moves += 1;
+            }
+            glutin_events_release(events.as_mut_ptr(), length);
+        }
+        assert_eq!(cursor_moves, 3);
+        assert_eq!(glutin_events_loop_get_overflow_events_count(event_loop), 0);
+
+        id.release();
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn panics_exit_the_loop_and_are_reported_by_the_next_run() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let id = window_id(window);
+        assert_eq!(
+            glutin_events_loop_inject_close_requested(event_loop, id),
+            GlutinResult::Ok
+        );
+
+        event_loop.with_not_null(|event_loop| {
+            event_loop.run_events(|c_event| match c_event.event_type {
+                GlutinEventType::WindowEventCloseRequested => panic!("Callback failed"),
+                _ => GlutinControlFlow::Poll,
+            })
+        });
+
+        let mut event_types = vec![];
+        event_loop.with_not_null(|event_loop| {
+            event_loop.pump_events(std::time::Duration::ZERO, |c_event| {
+                event_types.push(c_event.event_type);
+                GlutinControlFlow::Poll
+            });
+        });
+        assert!(event_types.contains(&GlutinEventType::Error));
+
+        id.release();
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+}
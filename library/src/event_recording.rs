@@ -0,0 +1,391 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use geometry_box::U128Box;
+use glutin::event::VirtualKeyCode;
+use string_box::StringBox;
+use value_box::{ValueBox, ValueBoxPointer};
+
+use crate::enums::GlutinResult;
+use crate::event_loop::GlutinEventLoop;
+use crate::events::{glutin_virtual_keycode, GlutinControlFlow, GlutinEvent, GlutinEventText};
+use crate::thread_guard::GlutinThreadGuardedPointer;
+
+/// Recorded events start with the magic bytes and the format version.
+/// Every event is stored as the time in microseconds since the start of the recording
+/// followed by the fields of the event in declaration order, see `RecordedValue`.
+/// Recordings can only be replayed by a library with the same event fields.
+const RECORDING_MAGIC: &[u8; 8] = b"GLUTINEV";
+const RECORDING_VERSION: u32 = 3;
+const NO_TEXT: u32 = u32::MAX;
+
+/// A value written to a recording independently of its memory layout. Numbers are stored
+/// little-endian, booleans as a byte, enums as their `u32` value and texts as a length-prefixed
+/// UTF-8 string. Reading a value validates it, a corrupted recording is an error.
+pub trait RecordedValue: Sized {
+    fn record(&self, writer: &mut impl Write) -> std::io::Result<()>;
+    fn replay(reader: &mut impl Read) -> std::io::Result<Self>;
+}
+
+macro_rules! recorded_number {
+    ($($number:ty),*) => {
+        $(impl RecordedValue for $number {
+            fn record(&self, writer: &mut impl Write) -> std::io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn replay(reader: &mut impl Read) -> std::io::Result<Self> {
+                let mut bytes = [0u8; std::mem::size_of::<$number>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$number>::from_le_bytes(bytes))
+            }
+        })*
+    };
+}
+
+recorded_number!(u8, u16, u32, u64, i32, i64, f32, f64);
+
+impl RecordedValue for usize {
+    fn record(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        (*self as u64).record(writer)
+    }
+
+    fn replay(reader: &mut impl Read) -> std::io::Result<Self> {
+        let value = u64::replay(reader)?;
+        usize::try_from(value).map_err(|_| glutin_invalid_record(format!("Invalid size {}", value)))
+    }
+}
+
+impl RecordedValue for bool {
+    fn record(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        (*self as u8).record(writer)
+    }
+
+    fn replay(reader: &mut impl Read) -> std::io::Result<Self> {
+        match u8::replay(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(glutin_invalid_record(format!("Invalid bool {}", value))),
+        }
+    }
+}
+
+impl RecordedValue for U128Box {
+    fn record(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        self.low.record(writer)?;
+        self.high.record(writer)
+    }
+
+    fn replay(reader: &mut impl Read) -> std::io::Result<Self> {
+        Ok(U128Box {
+            low: u64::replay(reader)?,
+            high: u64::replay(reader)?,
+        })
+    }
+}
+
+impl RecordedValue for VirtualKeyCode {
+    fn record(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        (*self as u32).record(writer)
+    }
+
+    fn replay(reader: &mut impl Read) -> std::io::Result<Self> {
+        let value = u32::replay(reader)?;
+        glutin_virtual_keycode(value)
+            .ok_or_else(|| glutin_invalid_record(format!("Invalid VirtualKeyCode {}", value)))
+    }
+}
+
+impl RecordedValue for GlutinEventText {
+    fn record(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        match self.string() {
+            None => NO_TEXT.record(writer),
+            Some(text) => {
+                (text.len() as u32).record(writer)?;
+                writer.write_all(text.as_bytes())
+            }
+        }
+    }
+
+    fn replay(reader: &mut impl Read) -> std::io::Result<Self> {
+        let mut c_text: GlutinEventText = Default::default();
+        let length = u32::replay(reader)?;
+        if length != NO_TEXT {
+            let mut text = vec![0u8; length as usize];
+            reader.read_exact(&mut text)?;
+            let text = String::from_utf8(text)
+                .map_err(|error| glutin_invalid_record(format!("Invalid text: {}", error)))?;
+            c_text.set_string(text);
+        }
+        Ok(c_text)
+    }
+}
+
+/// Record a struct field by field given all of its fields in declaration order.
+/// Fails to compile if a field of the struct is not listed.
+#[macro_export]
+macro_rules! recorded_struct {
+    ($name:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::event_recording::RecordedValue for $name {
+            fn record(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+                // an exhaustive destructuring, like in `ffi_struct!`, so that a field added
+                // to the struct can not be left out of the recording
+                let $name { $($field),* } = self;
+                $($crate::event_recording::RecordedValue::record($field, writer)?;)*
+                Ok(())
+            }
+
+            fn replay(reader: &mut impl std::io::Read) -> std::io::Result<Self> {
+                Ok($name {
+                    $($field: $crate::event_recording::RecordedValue::replay(reader)?),*
+                })
+            }
+        }
+    };
+}
+
+/// Record an enum as its `u32` value given all of its variants.
+/// Fails to compile if a variant of the enum is not listed.
+#[macro_export]
+macro_rules! recorded_enum {
+    ($name:ident { $($variant:ident),* $(,)? }) => {
+        impl $crate::event_recording::RecordedValue for $name {
+            fn record(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+                let value = match self {
+                    $($name::$variant => $name::$variant as u32),*
+                };
+                $crate::event_recording::RecordedValue::record(&value, writer)
+            }
+
+            fn replay(reader: &mut impl std::io::Read) -> std::io::Result<Self> {
+                let value: u32 = $crate::event_recording::RecordedValue::replay(reader)?;
+                $(if value == $name::$variant as u32 {
+                    return Ok($name::$variant);
+                })*
+                Err($crate::event_recording::glutin_invalid_record(format!(
+                    "Invalid {} {}",
+                    stringify!($name),
+                    value
+                )))
+            }
+        }
+    };
+}
+
+pub fn glutin_invalid_record(message: String) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Writes translated events to a file
+#[derive(Debug)]
+pub struct EventRecorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl EventRecorder {
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(RECORDING_MAGIC)?;
+        RECORDING_VERSION.record(&mut writer)?;
+
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, c_event: &GlutinEvent) -> std::io::Result<()> {
+        let timestamp = self.start.elapsed().as_micros() as u64;
+        timestamp.record(&mut self.writer)?;
+        c_event.record(&mut self.writer)
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reads events written by the `EventRecorder`
+pub struct EventPlayer {
+    reader: BufReader<File>,
+}
+
+impl EventPlayer {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != RECORDING_MAGIC {
+            return Err(glutin_invalid_record(String::from(
+                "Not an event recording",
+            )));
+        }
+
+        let version = u32::replay(&mut reader)?;
+        if version != RECORDING_VERSION {
+            return Err(glutin_invalid_record(format!(
+                "Incompatible recording version {}",
+                version
+            )));
+        }
+
+        Ok(Self { reader })
+    }
+
+    /// Read the next event and the time in microseconds since the start of the recording,
+    /// or None at the end of the recording
+    pub fn next_event(&mut self) -> std::io::Result<Option<(u64, GlutinEvent)>> {
+        let timestamp = match u64::replay(&mut self.reader) {
+            Ok(timestamp) => timestamp,
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        Ok(Some((timestamp, GlutinEvent::replay(&mut self.reader)?)))
+    }
+}
+
+/// Start recording the translated events of the event loop into a file at a given path,
+/// replacing a recording in progress. Returns false if the file could not be created.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_start_recording(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_path: *mut ValueBox<StringBox>,
+) -> bool {
+    _ptr_events_loop.with_owned_return(false, |event_loop| {
+        _ptr_path.with_not_null_return(false, |path| {
+            match EventRecorder::create(path.to_string()) {
+                Ok(recorder) => {
+                    glutin_events_loop_finish_recording(event_loop);
+                    event_loop.event_recorder = Some(recorder);
+                    true
+                }
+                Err(error) => {
+                    error!("[glutin_events_loop_start_recording] {}", error);
+                    false
+                }
+            }
+        })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_stop_recording(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+) -> GlutinResult {
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        glutin_events_loop_finish_recording(event_loop);
+        GlutinResult::Ok
+    })
+}
+
+fn glutin_events_loop_finish_recording(event_loop: &mut GlutinEventLoop) {
+    if let Some(recorder) = event_loop.event_recorder.take() {
+        if let Err(error) = recorder.finish() {
+            error!("Failed to finish the event recording: {}", error);
+        }
+    }
+}
+
+/// Replay a recording into the callback through the event filter of the event loop.
+/// A `speed` of 1.0 keeps the original timing, larger values accelerate the replay and
+/// 0.0 sends the events as fast as possible. Replay stops when the callback returns `Exit`.
+/// Returns false if the recording could not be read.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_replay(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    _ptr_path: *mut ValueBox<StringBox>,
+    speed: f64,
+    callback: extern "C" fn(*mut GlutinEvent) -> GlutinControlFlow,
+) -> bool {
+    _ptr_events_loop.with_owned_return(false, |event_loop| {
+        _ptr_path.with_not_null_return(false, |path| {
+            let mut player = match EventPlayer::open(path.to_string()) {
+                Ok(player) => player,
+                Err(error) => {
+                    error!("[glutin_events_loop_replay] {}", error);
+                    return false;
+                }
+            };
+
+            let start = Instant::now();
+            let mut is_exit_requested = false;
+            while !is_exit_requested {
+                let (timestamp, c_event) = match player.next_event() {
+                    Ok(Some(next)) => next,
+                    Ok(None) => break,
+                    Err(error) => {
+                        error!("[glutin_events_loop_replay] {}", error);
+                        event_loop.event_filter.discard_pending();
+                        return false;
+                    }
+                };
+
+                if speed > 0.0 {
+                    let due = Duration::from_micros((timestamp as f64 / speed) as u64);
+                    let elapsed = start.elapsed();
+                    if due > elapsed {
+                        std::thread::sleep(due - elapsed);
+                    }
+                }
+
+                event_loop.event_filter.dispatch(c_event, |c_event| {
+                    if let GlutinControlFlow::Exit = callback(c_event) {
+                        is_exit_requested = true;
+                    }
+                });
+            }
+
+            // the end of the recording does not end the batch of a coalesced event
+            if is_exit_requested {
+                event_loop.event_filter.discard_pending();
+            } else {
+                event_loop.event_filter.flush(|c_event| {
+                    callback(c_event);
+                });
+            }
+            true
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::{GlutinEvent, GlutinEventType};
+
+    use super::RecordedValue;
+
+    #[test]
+    fn events_are_replayed_as_recorded() {
+        let mut c_event = GlutinEvent::error(String::from("Recorded"), 42);
+        c_event.window_id.low = 7;
+        c_event.is_synthetic = true;
+
+        let mut bytes = vec![];
+        c_event.record(&mut bytes).unwrap();
+        let replayed = GlutinEvent::replay(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(replayed.event_type, GlutinEventType::Error);
+        assert_eq!(replayed.window_id.low, 7);
+        assert_eq!(replayed.wait_timeout, 42);
+        assert!(replayed.is_synthetic);
+        assert_eq!(replayed.text.string(), Some(String::from("Recorded")));
+    }
+
+    #[test]
+    fn corrupted_events_are_rejected() {
+        let mut bytes = vec![];
+        GlutinEvent::default().record(&mut bytes).unwrap();
+
+        // the event type follows the 16 bytes of the window id
+        let mut invalid_type = bytes.clone();
+        invalid_type[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(GlutinEvent::replay(&mut invalid_type.as_slice()).is_err());
+
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(GlutinEvent::replay(&mut &truncated[..]).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::ffi::c_void;
+
+use value_box::ValueBox;
+
+use crate::enums::GlutinResult;
+use crate::event_filter::glutin_event_type_bit;
+use crate::event_loop::GlutinEventLoop;
+use crate::events::{GlutinControlFlow, GlutinEvent, GlutinEventType};
+use crate::ffi_manifest::FfiStruct;
+use crate::thread_guard::GlutinThreadGuardedPointer;
+
+/// A fixed-capacity ring buffer of events owned by the host. The library appends translated
+/// events at the tail and the host consumes them from the head with
+/// `glutin_event_ring_buffer_consume`. Events that do not fit are dropped and counted.
+#[derive(Debug)]
+#[repr(C)]
+pub struct GlutinEventRingBuffer {
+    /// host-allocated array of `capacity` events
+    events: *mut GlutinEvent,
+    capacity: usize,
+    /// index of the oldest unconsumed event
+    head: usize,
+    /// amount of unconsumed events
+    length: usize,
+    /// amount of events dropped because the buffer was full
+    overflow: u64,
+    /// microseconds to wait for new events if the batch callback returns `WaitUntil`,
+    /// set to the wait timeout of the event loop when the batched run starts
+    wait_timeout: u64,
+}
+
+impl GlutinEventRingBuffer {
+    fn is_valid(&self) -> bool {
+        !self.events.is_null() && self.capacity > 0
+    }
+
+    fn push(&mut self, event: GlutinEvent) {
+        if self.length == self.capacity {
+            self.overflow += 1;
+            return;
+        }
+        let index = (self.head + self.length) % self.capacity;
+        unsafe { std::ptr::write(self.events.add(index), event) };
+        self.length += 1;
+    }
+
+    fn consume(&mut self, amount: usize) {
+        for _ in 0..amount.min(self.length) {
+            unsafe { std::ptr::drop_in_place(self.events.add(self.head)) };
+            self.head = (self.head + 1) % self.capacity;
+            self.length -= 1;
+        }
+    }
+}
+
+/// Initialize a ring buffer header over a host-allocated array of `capacity` events.
+/// The previous content of the header is overwritten without being read, so it may be
+/// uninitialized memory. To reuse an initialized header with another array, release it
+/// with `glutin_event_ring_buffer_release` before initializing it again.
+#[no_mangle]
+pub extern "C" fn glutin_event_ring_buffer_init(
+    buffer: *mut GlutinEventRingBuffer,
+    events: *mut GlutinEvent,
+    capacity: usize,
+) {
+    if buffer.is_null() {
+        error!("[glutin_event_ring_buffer_init] buffer is null");
+        return;
+    }
+    unsafe {
+        std::ptr::write(
+            buffer,
+            GlutinEventRingBuffer {
+                events,
+                capacity,
+                head: 0,
+                length: 0,
+                overflow: 0,
+                wait_timeout: 0,
+            },
+        )
+    };
+}
+
+/// Release the payloads of all unconsumed events and detach the buffer from its array,
+/// call it before the array is freed
+#[no_mangle]
+pub extern "C" fn glutin_event_ring_buffer_release(buffer: *mut GlutinEventRingBuffer) {
+    if buffer.is_null() {
+        return;
+    }
+    let buffer = unsafe { &mut *buffer };
+    if buffer.is_valid() {
+        buffer.consume(buffer.length);
+    }
+    buffer.events = std::ptr::null_mut();
+    buffer.capacity = 0;
+    buffer.head = 0;
+    buffer.length = 0;
+}
+
+/// Release the payloads of the oldest `amount` events and advance the head of the buffer
+#[no_mangle]
+pub extern "C" fn glutin_event_ring_buffer_consume(
+    buffer: *mut GlutinEventRingBuffer,
+    amount: usize,
+) {
+    if buffer.is_null() {
+        return;
+    }
+    let buffer = unsafe { &mut *buffer };
+    if buffer.is_valid() {
+        buffer.consume(amount);
+    }
+}
+
+/// Run the event loop, writing translated events into the ring buffer instead of sending
+/// them one by one. The callback is called once per batch when the loop is about to
+/// redraw or go idle (`MainEventsCleared` and `RedrawEventsCleared`) and when it is destroyed,
+/// its result controls the loop like the result of the per-event callback.
+/// Batches end whether or not these events are subscribed in the event mask,
+/// `LoopDestroyed` is written into the buffer only if it is.
+#[no_mangle]
+pub extern "C" fn glutin_events_loop_run_return_batched(
+    _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
+    buffer: *mut GlutinEventRingBuffer,
+    callback: extern "C" fn(*mut c_void, *mut GlutinEventRingBuffer) -> GlutinControlFlow,
+    user_data: *mut c_void,
+) -> GlutinResult {
+    if buffer.is_null() || !unsafe { &*buffer }.is_valid() {
+        error!("[glutin_events_loop_run_return_batched] buffer is not initialized");
+        return GlutinResult::NullPointer;
+    }
+
+    _ptr_events_loop.with_owned_result(|event_loop| {
+        unsafe { &mut *buffer }.wait_timeout = event_loop.wait_timeout.as_micros() as u64;
+
+        let batch_ends = [
+            GlutinEventType::MainEventsCleared,
+            GlutinEventType::RedrawEventsCleared,
+            GlutinEventType::LoopDestroyed,
+        ]
+        .into_iter()
+        .fold(0, |bits, event_type| {
+            bits | glutin_event_type_bit(event_type)
+        });
+        let is_loop_destroyed_subscribed = event_loop
+            .event_filter
+            .is_subscribed(GlutinEventType::LoopDestroyed);
+        event_loop.event_filter.always_delivered |= batch_ends;
+
+        let mut control_flow = GlutinControlFlow::Poll;
+        event_loop.run_events(|c_event| {
+            let ring_buffer = unsafe { &mut *buffer };
+            // the main events are always announced, other batch ends only if there are events
+            let is_batch_end = match c_event.event_type {
+                GlutinEventType::MainEventsCleared => true,
+                GlutinEventType::RedrawEventsCleared => ring_buffer.length > 0,
+                GlutinEventType::LoopDestroyed => {
+                    if is_loop_destroyed_subscribed {
+                        ring_buffer.push(std::mem::take(c_event));
+                    }
+                    true
+                }
+                _ => {
+                    ring_buffer.push(std::mem::take(c_event));
+                    false
+                }
+            };
+
+            if is_batch_end {
+                control_flow = callback(user_data, buffer);
+            }
+
+            c_event.wait_timeout = unsafe { &*buffer }.wait_timeout;
+            control_flow
+        });
+
+        event_loop.event_filter.always_delivered &= !batch_ends;
+        GlutinResult::Ok
+    })
+}
+
+pub(crate) fn glutin_event_ring_buffer_layout() -> FfiStruct {
+    crate::ffi_struct!(GlutinEventRingBuffer {
+        events,
+        capacity,
+        head,
+        length,
+        overflow,
+        wait_timeout
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::MaybeUninit;
+
+    use super::*;
+    use crate::event_filter::{
+        glutin_events_loop_get_event_mask, glutin_events_loop_set_event_mask,
+    };
+    use crate::event_loop::{glutin_destroy_events_loop, glutin_events_loop_set_wait_timeout};
+    use crate::mock::fixtures::create_mock_event_loop;
+
+    #[test]
+    fn batches_end_without_subscribed_batch_events() {
+        extern "C" fn count_batch(
+            user_data: *mut std::ffi::c_void,
+            _buffer: *mut GlutinEventRingBuffer,
+        ) -> GlutinControlFlow {
+            unsafe { *(user_data as *mut usize) += 1 };
+            GlutinControlFlow::Exit
+        }
+
+        let event_loop = create_mock_event_loop();
+        glutin_events_loop_set_event_mask(event_loop, 0);
+        // the buffer owns the written events, the vector only provides the storage
+        let mut events: Vec<GlutinEvent> = Vec::with_capacity(4);
+        let mut buffer = MaybeUninit::<GlutinEventRingBuffer>::uninit();
+        glutin_event_ring_buffer_init(buffer.as_mut_ptr(), events.as_mut_ptr(), events.capacity());
+        let mut buffer = unsafe { buffer.assume_init() };
+
+        let mut batches = 0usize;
+        glutin_events_loop_run_return_batched(
+            event_loop,
+            &mut buffer,
+            count_batch,
+            &mut batches as *mut usize as *mut std::ffi::c_void,
+        );
+        assert!(batches > 0);
+        assert_eq!(glutin_events_loop_get_event_mask(event_loop), 0);
+
+        glutin_event_ring_buffer_release(&mut buffer);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn batched_run_waits_for_the_timeout_of_the_event_loop() {
+        extern "C" fn read_wait_timeout(
+            user_data: *mut c_void,
+            buffer: *mut GlutinEventRingBuffer,
+        ) -> GlutinControlFlow {
+            unsafe { *(user_data as *mut u64) = (*buffer).wait_timeout };
+            GlutinControlFlow::Exit
+        }
+
+        let event_loop = create_mock_event_loop();
+        glutin_events_loop_set_wait_timeout(event_loop, 1234);
+        let mut events: Vec<GlutinEvent> = Vec::with_capacity(4);
+        let mut buffer = MaybeUninit::<GlutinEventRingBuffer>::uninit();
+        glutin_event_ring_buffer_init(buffer.as_mut_ptr(), events.as_mut_ptr(), events.capacity());
+        let mut buffer = unsafe { buffer.assume_init() };
+
+        let mut wait_timeout = 0u64;
+        assert_eq!(
+            glutin_events_loop_run_return_batched(
+                event_loop,
+                &mut buffer,
+                read_wait_timeout,
+                &mut wait_timeout as *mut u64 as *mut c_void,
+            ),
+            GlutinResult::Ok
+        );
+        assert_eq!(wait_timeout, 1234);
+
+        glutin_event_ring_buffer_release(&mut buffer);
+        glutin_destroy_events_loop(event_loop);
+    }
+}
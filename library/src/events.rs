@@ -1,12 +1,18 @@
+use std::any::Any;
 use std::collections::HashMap;
-use std::mem::transmute;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::Instant;
 
 use geometry_box::U128Box;
 use glutin::dpi::{PhysicalPosition, PhysicalSize};
 use glutin::event::*;
+use string_box::StringBox;
+use value_box::{ValueBox, ValueBoxPointer};
 
-use crate::event_loop::GlutinCustomEvent;
-use crate::glutin_convert_window_id;
+use crate::event_loop::{GlutinCustomEvent, GlutinLoopEvent};
+use crate::ffi_manifest::{FfiEnum, FfiStruct};
+use crate::{glutin_convert_device_id, glutin_convert_window_id};
 
 #[derive(Debug, Default)]
 #[repr(C)]
@@ -25,6 +31,19 @@ pub struct GlutinEvent {
     pub window_focused: GlutinWindowFocusedEvent,
     pub modifiers: GlutinEventModifiersState,
     pub user_event: GlutinEventUserEvent,
+    pub text: GlutinEventText,
+    pub device_motion: GlutinDeviceMotionEvent,
+    pub device_axis: GlutinDeviceAxisEvent,
+    pub device_button: GlutinDeviceButtonEvent,
+    pub device_key: GlutinDeviceKeyEvent,
+    pub touchpad_pressure: GlutinTouchpadPressureEvent,
+    pub axis_motion: GlutinAxisMotionEvent,
+    pub new_events: GlutinNewEventsEvent,
+    /// Microseconds to wait for new events if the callback returns `WaitUntil`.
+    /// Initialized to the default wait timeout of the event loop, the callback may change it.
+    pub wait_timeout: u64,
+    /// True if the event was injected by the host instead of received from the windowing system
+    pub is_synthetic: bool,
 }
 
 #[derive(Debug, Default)]
@@ -36,6 +55,38 @@ pub struct GlutinTouchEvent {
     y: f64,
     /// unique identifier of a finger.
     id: u64,
+    /// the kind of force reported by the device, if any.
+    force_type: GlutinEventTouchForceType,
+    /// the force of the touch in the range from 0.0 to 1.0 for any kind of force.
+    normalized_force: f64,
+    /// the calibrated force, where 1.0 is the force of an average touch.
+    force: f64,
+    /// the maximum possible calibrated force.
+    max_possible_force: f64,
+    /// true if the altitude angle of a stylus is known.
+    has_altitude_angle: bool,
+    /// the altitude angle of a stylus in radians, where PI/2 is perpendicular to the surface.
+    altitude_angle: f64,
+}
+
+/// Pressure on a touchpad, only reported by Apple's force touch trackpads
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct GlutinTouchpadPressureEvent {
+    device_id: i64,
+    /// pressure in the range from 0.0 to 1.0
+    pressure: f32,
+    /// the click level of the touchpad
+    stage: i64,
+}
+
+/// Motion on some analog axis within a window, such as a pen tilt
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct GlutinAxisMotionEvent {
+    device_id: i64,
+    axis: u32,
+    value: f64,
 }
 
 #[derive(Debug, Default)]
@@ -44,6 +95,7 @@ pub struct GlutinMouseWheelEvent {
     device_id: i64,
     phase: GlutinEventTouchPhase,
     delta: GlutinMouseScrollDelta,
+    modifiers: GlutinEventModifiersState,
 }
 
 #[derive(Debug, Copy, Clone, Default)]
@@ -52,6 +104,7 @@ pub struct GlutinMouseInputEvent {
     device_id: i64,
     state: GlutinEventInputElementState,
     button: GlutinEventMouseButton,
+    modifiers: GlutinEventModifiersState,
 }
 
 #[derive(Debug, Copy, Clone, Default)]
@@ -60,6 +113,7 @@ pub struct GlutinCursorMovedEvent {
     device_id: i64,
     x: f64,
     y: f64,
+    modifiers: GlutinEventModifiersState,
 }
 
 #[derive(Debug, Copy, Clone, Default)]
@@ -99,6 +153,17 @@ pub struct GlutinEventKeyboardInput {
     has_virtual_keycode: bool,
     virtual_keycode: VirtualKeyCode,
     is_synthetic: bool,
+    modifiers: GlutinEventModifiersState,
+    /// the location of the key on the keyboard, to tell apart keys such as left and right shift
+    location: GlutinEventKeyLocation,
+    /// what the key means in the current keyboard layout, a named key or a character
+    logical_key: GlutinEventLogicalKey,
+    /// the unicode code point of the character of a `Character` logical key, for example 'a'
+    /// for the key right of Caps Lock on a QWERTY layout and 'q' for the same key on AZERTY.
+    /// It is read unmodified from the keyboard layout of X11 displays. Other platforms do not
+    /// expose the layout, the character is then taken from the text the key produced with
+    /// Shift, Caps Lock and Ctrl undone, so it can still include the effect of AltGr.
+    logical_character: u32,
 }
 
 impl Default for GlutinEventKeyboardInput {
@@ -110,6 +175,10 @@ impl Default for GlutinEventKeyboardInput {
             has_virtual_keycode: Default::default(),
             virtual_keycode: VirtualKeyCode::Unlabeled,
             is_synthetic: false,
+            modifiers: Default::default(),
+            location: Default::default(),
+            logical_key: Default::default(),
+            logical_character: 0,
         }
     }
 }
@@ -145,6 +214,140 @@ pub struct GlutinEventModifiersState {
     ///
     /// This is the "windows" key on PC and "command" key on Mac.
     logo: bool,
+    left_shift: bool,
+    right_shift: bool,
+    left_ctrl: bool,
+    right_ctrl: bool,
+    left_alt: bool,
+    /// The right "alt" key, which is "AltGr" on many keyboard layouts
+    right_alt: bool,
+    left_logo: bool,
+    right_logo: bool,
+    /// Caps Lock is toggled on. The state is read from the windowing system where possible,
+    /// otherwise it is tracked from key presses and a lock toggled before the application
+    /// started is not known until it is pressed.
+    caps_lock: bool,
+    /// Num Lock is toggled on, known the same way as Caps Lock
+    num_lock: bool,
+}
+
+/// The toggled state of the lock keys as reported by the windowing system
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlutinLockKeys {
+    pub caps_lock: bool,
+    pub num_lock: bool,
+}
+
+/// Reads the lock keys from the windowing system, returns None if they can not be read
+pub type GlutinLockKeysQuery = Box<dyn Fn() -> Option<GlutinLockKeys>>;
+
+/// Looks up the unmodified character of a key in the active keyboard layout of the windowing
+/// system, returns None if the key does not produce a character or the layout can not be read
+pub type GlutinLogicalCharacterQuery = Box<dyn Fn(ScanCode) -> Option<char>>;
+
+impl GlutinEventModifiersState {
+    fn update_from_modifiers(&mut self, modifiers: ModifiersState) {
+        self.shift = modifiers.shift();
+        self.ctrl = modifiers.ctrl();
+        self.alt = modifiers.alt();
+        self.logo = modifiers.logo();
+        // the sides are only known from key events. They are not reset here, because
+        // some platforms report AltGr as another modifier than alt.
+    }
+
+    fn update_lock_keys(&mut self, lock_keys: GlutinLockKeys) {
+        self.caps_lock = lock_keys.caps_lock;
+        self.num_lock = lock_keys.num_lock;
+    }
+
+    /// Update the modifiers from a key event, returns true if the key is a lock key
+    fn update_from_key(
+        &mut self,
+        scan_code: ScanCode,
+        virtual_keycode: Option<VirtualKeyCode>,
+        state: ElementState,
+    ) -> bool {
+        let is_pressed = state == ElementState::Pressed;
+
+        match glutin_modifier_key(scan_code, virtual_keycode) {
+            Some(ModifierKey::LeftShift) => self.left_shift = is_pressed,
+            Some(ModifierKey::RightShift) => self.right_shift = is_pressed,
+            Some(ModifierKey::LeftCtrl) => self.left_ctrl = is_pressed,
+            Some(ModifierKey::RightCtrl) => self.right_ctrl = is_pressed,
+            Some(ModifierKey::LeftAlt) => self.left_alt = is_pressed,
+            Some(ModifierKey::RightAlt) => self.right_alt = is_pressed,
+            Some(ModifierKey::LeftLogo) => self.left_logo = is_pressed,
+            Some(ModifierKey::RightLogo) => self.right_logo = is_pressed,
+            Some(ModifierKey::CapsLock) => {
+                if is_pressed {
+                    self.caps_lock = !self.caps_lock;
+                }
+                return true;
+            }
+            Some(ModifierKey::NumLock) => {
+                if is_pressed {
+                    self.num_lock = !self.num_lock;
+                }
+                return true;
+            }
+            None => return false,
+        }
+
+        self.shift = self.left_shift || self.right_shift;
+        self.ctrl = self.left_ctrl || self.right_ctrl;
+        self.alt = self.left_alt || self.right_alt;
+        self.logo = self.left_logo || self.right_logo;
+        false
+    }
+}
+
+enum ModifierKey {
+    LeftShift,
+    RightShift,
+    LeftCtrl,
+    RightCtrl,
+    LeftAlt,
+    RightAlt,
+    LeftLogo,
+    RightLogo,
+    CapsLock,
+    NumLock,
+}
+
+fn glutin_modifier_key(
+    scan_code: ScanCode,
+    virtual_keycode: Option<VirtualKeyCode>,
+) -> Option<ModifierKey> {
+    match virtual_keycode {
+        Some(VirtualKeyCode::LShift) => Some(ModifierKey::LeftShift),
+        Some(VirtualKeyCode::RShift) => Some(ModifierKey::RightShift),
+        Some(VirtualKeyCode::LControl) => Some(ModifierKey::LeftCtrl),
+        Some(VirtualKeyCode::RControl) => Some(ModifierKey::RightCtrl),
+        Some(VirtualKeyCode::LAlt) => Some(ModifierKey::LeftAlt),
+        Some(VirtualKeyCode::RAlt) => Some(ModifierKey::RightAlt),
+        Some(VirtualKeyCode::LWin) => Some(ModifierKey::LeftLogo),
+        Some(VirtualKeyCode::RWin) => Some(ModifierKey::RightLogo),
+        Some(VirtualKeyCode::Capital) => Some(ModifierKey::CapsLock),
+        Some(VirtualKeyCode::Numlock) => Some(ModifierKey::NumLock),
+        Some(_) => None,
+        None => glutin_modifier_key_from_scan_code(scan_code),
+    }
+}
+
+/// X11 does not map "AltGr" (ISO_Level3_Shift) and Caps Lock to virtual keycodes,
+/// we recognise them by their evdev scan codes instead
+#[cfg(target_os = "linux")]
+fn glutin_modifier_key_from_scan_code(scan_code: ScanCode) -> Option<ModifierKey> {
+    match scan_code {
+        58 => Some(ModifierKey::CapsLock),
+        100 => Some(ModifierKey::RightAlt),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn glutin_modifier_key_from_scan_code(_scan_code: ScanCode) -> Option<ModifierKey> {
+    None
 }
 
 #[derive(Debug, Copy, Clone, Default)]
@@ -160,6 +363,239 @@ pub struct GlutinEventUserEvent {
     event: GlutinCustomEvent,
 }
 
+impl GlutinEvent {
+    /// An `Error` event carrying the message as its text
+    pub fn error(message: String, wait_timeout: u64) -> Self {
+        let mut c_event = GlutinEvent {
+            event_type: GlutinEventType::Error,
+            wait_timeout,
+            ..Default::default()
+        };
+        c_event.text.set_string(message);
+        c_event
+    }
+
+    /// Merge a newer event into this one if both are cursor moves or scrolls of the same device
+    /// within the same window. Returns false if the events can not be merged.
+    pub fn coalesce(&mut self, newer: &GlutinEvent) -> bool {
+        if self.event_type != newer.event_type
+            || self.window_id.low != newer.window_id.low
+            || self.window_id.high != newer.window_id.high
+        {
+            return false;
+        }
+
+        match self.event_type {
+            GlutinEventType::WindowEventCursorMoved => {
+                if self.cursor_moved.device_id != newer.cursor_moved.device_id {
+                    return false;
+                }
+                self.cursor_moved = newer.cursor_moved;
+                true
+            }
+            GlutinEventType::WindowEventMouseWheel => {
+                let wheel = &mut self.mouse_wheel;
+                let newer_wheel = &newer.mouse_wheel;
+                // keep the start and the end of a scroll gesture as separate events
+                if wheel.device_id != newer_wheel.device_id
+                    || wheel.phase != GlutinEventTouchPhase::Moved
+                    || newer_wheel.phase != GlutinEventTouchPhase::Moved
+                    || wheel.delta.delta_type != newer_wheel.delta.delta_type
+                {
+                    return false;
+                }
+                wheel.delta.x += newer_wheel.delta.x;
+                wheel.delta.y += newer_wheel.delta.y;
+                wheel.modifiers = newer_wheel.modifiers;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Return true if the event is a key press that may be followed by the character it produces
+    pub fn is_key_press_without_text(&self) -> bool {
+        self.event_type == GlutinEventType::WindowEventKeyboardInput
+            && matches!(
+                self.keyboard_input.state,
+                GlutinEventInputElementState::Pressed
+            )
+            && !self.text.has_string()
+    }
+
+    /// Attach the text of a received character to this key press if the character follows
+    /// the press in the same window, which is how every platform reports the text of a key.
+    /// A key without a known logical key becomes a `Character` key.
+    /// Returns false if the character does not belong to the key.
+    pub fn attach_key_text(&mut self, character: &GlutinEvent) -> bool {
+        if !self.is_key_press_without_text()
+            || character.event_type != GlutinEventType::WindowEventReceivedCharacter
+            || self.window_id.low != character.window_id.low
+            || self.window_id.high != character.window_id.high
+        {
+            return false;
+        }
+        let text = match character.text.string() {
+            Some(text) => text,
+            None => return false,
+        };
+
+        let key = &mut self.keyboard_input;
+        if key.logical_key == GlutinEventLogicalKey::Unidentified {
+            if let Some(character) = text
+                .chars()
+                .next()
+                .and_then(|character| glutin_unmodified_character(character, &key.modifiers))
+            {
+                key.logical_key = GlutinEventLogicalKey::Character;
+                key.logical_character = character as u32;
+            }
+        }
+        self.text.set_string(text);
+        true
+    }
+
+    /// The scan code and the logical character of a key event with a `Character` logical key
+    pub fn key_logical_character(&self) -> Option<(u32, u32)> {
+        let key = &self.keyboard_input;
+        if self.event_type != GlutinEventType::WindowEventKeyboardInput
+            || key.logical_key != GlutinEventLogicalKey::Character
+        {
+            return None;
+        }
+        Some((key.scan_code, key.logical_character))
+    }
+
+    /// Give a key release the logical character of its press, releases produce no text
+    /// to identify them by
+    pub fn set_released_key_character(&mut self, characters: &mut HashMap<u32, u32>) {
+        let key = &mut self.keyboard_input;
+        if self.event_type != GlutinEventType::WindowEventKeyboardInput
+            || !matches!(key.state, GlutinEventInputElementState::Released)
+        {
+            return;
+        }
+        if let Some(character) = characters.remove(&key.scan_code) {
+            if key.logical_key == GlutinEventLogicalKey::Unidentified {
+                key.logical_key = GlutinEventLogicalKey::Character;
+                key.logical_character = character;
+            }
+        }
+    }
+}
+
+/// A variable-length text payload of an event, such as a received character, the character
+/// produced by a key press or a dropped file path.
+/// The string is owned by the event and is released together with it by `glutin_event_drop`,
+/// the host must copy it if it needs the text after the callback returns.
+/// The string is null for events without text.
+#[derive(Debug)]
+#[repr(C)]
+pub struct GlutinEventText {
+    string: *mut ValueBox<StringBox>,
+}
+
+impl GlutinEventText {
+    pub fn set_string(&mut self, string: String) {
+        self.release();
+        self.string = ValueBox::new(StringBox::from_string(string)).into_raw();
+    }
+
+    pub fn has_string(&self) -> bool {
+        !self.string.is_null()
+    }
+
+    pub fn string(&self) -> Option<String> {
+        self.string
+            .with_not_null_return(None, |string| Some(string.to_string()))
+    }
+
+    fn release(&mut self) {
+        if !self.string.is_null() {
+            self.string.release();
+            self.string = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Default for GlutinEventText {
+    fn default() -> Self {
+        GlutinEventText {
+            string: std::ptr::null_mut(),
+        }
+    }
+}
+
+impl Drop for GlutinEventText {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Describes why the event loop woke up. All times are in microseconds since
+/// the creation of the event loop.
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct GlutinNewEventsEvent {
+    cause: GlutinEventStartCause,
+    /// the moment the event loop stopped waiting
+    start: u64,
+    /// true if the loop was waiting until a requested resume time
+    has_requested_resume: bool,
+    /// the resume time requested by the control flow
+    requested_resume: u64,
+    /// the moment the event was translated, to measure the latency of the loop
+    now: u64,
+}
+
+/// Unaccelerated and unfiltered mouse motion, not tied to any window
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct GlutinDeviceMotionEvent {
+    device_id: i64,
+    delta_x: f64,
+    delta_y: f64,
+}
+
+/// Raw motion on some analog axis of a device
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct GlutinDeviceAxisEvent {
+    device_id: i64,
+    axis: u32,
+    value: f64,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[repr(C)]
+pub struct GlutinDeviceButtonEvent {
+    device_id: i64,
+    button: u32,
+    state: GlutinEventInputElementState,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct GlutinDeviceKeyEvent {
+    device_id: i64,
+    scan_code: u32,
+    state: GlutinEventInputElementState,
+    has_virtual_keycode: bool,
+    virtual_keycode: VirtualKeyCode,
+}
+
+impl Default for GlutinDeviceKeyEvent {
+    fn default() -> Self {
+        GlutinDeviceKeyEvent {
+            device_id: Default::default(),
+            scan_code: Default::default(),
+            state: Default::default(),
+            has_virtual_keycode: Default::default(),
+            virtual_keycode: VirtualKeyCode::Unlabeled,
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /////////////////////////////////// S T R U C T S  ////////////////////////////////////
 ///////////////////////////////////////////////////////////////////////////////////////
@@ -212,6 +648,12 @@ pub enum GlutinEventType {
     RedrawEventsCleared,
     ModifiersChanged,
     UserEvent,
+    DeviceEventMouseMotion,
+    DeviceEventMotion,
+    DeviceEventButton,
+    DeviceEventKey,
+    /// The library failed to translate an event, the text of the event describes the error
+    Error,
 }
 
 impl Default for GlutinEventType {
@@ -220,7 +662,7 @@ impl Default for GlutinEventType {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(u32)]
 pub enum GlutinEventTouchPhase {
     Unknown,
@@ -236,7 +678,230 @@ impl Default for GlutinEventTouchPhase {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum GlutinEventStartCause {
+    Unknown,
+    /// Sent once, immediately after the event loop is started
+    Init,
+    /// Sent if the control flow was set to poll
+    Poll,
+    /// Sent if the loop stopped waiting before the requested resume time,
+    /// usually because a new event arrived
+    WaitCancelled,
+    /// Sent if the requested resume time was reached
+    ResumeTimeReached,
+}
+
+impl Default for GlutinEventStartCause {
+    fn default() -> Self {
+        GlutinEventStartCause::Unknown
+    }
+}
+
+/// Convert the value of winit's `VirtualKeyCode` received from the host or read from a
+/// recording. Returns None if the value is out of range.
+pub(crate) fn glutin_virtual_keycode(value: u32) -> Option<VirtualKeyCode> {
+    // the variants of the #[repr(u32)] enum in winit have implicit values from 0 to `Cut`
+    if value > VirtualKeyCode::Cut as u32 {
+        return None;
+    }
+    Some(unsafe { std::mem::transmute::<u32, VirtualKeyCode>(value) })
+}
+
+/// What a key means in the current keyboard layout
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum GlutinEventLogicalKey {
+    /// The meaning of the key is not known
+    Unidentified,
+    /// A key that does not produce a character, such as Enter or an arrow key. It is named
+    /// by its `virtual_keycode`.
+    Named,
+    /// A key that produces the `logical_character`
+    Character,
+}
+
+impl Default for GlutinEventLogicalKey {
+    fn default() -> Self {
+        GlutinEventLogicalKey::Unidentified
+    }
+}
+
+/// Return true if the key does not produce a character, such as a function, navigation,
+/// editing, modifier or media key
+fn glutin_is_named_key(code: VirtualKeyCode) -> bool {
+    !matches!(
+        code,
+        VirtualKeyCode::Key1
+            | VirtualKeyCode::Key2
+            | VirtualKeyCode::Key3
+            | VirtualKeyCode::Key4
+            | VirtualKeyCode::Key5
+            | VirtualKeyCode::Key6
+            | VirtualKeyCode::Key7
+            | VirtualKeyCode::Key8
+            | VirtualKeyCode::Key9
+            | VirtualKeyCode::Key0
+            | VirtualKeyCode::A
+            | VirtualKeyCode::B
+            | VirtualKeyCode::C
+            | VirtualKeyCode::D
+            | VirtualKeyCode::E
+            | VirtualKeyCode::F
+            | VirtualKeyCode::G
+            | VirtualKeyCode::H
+            | VirtualKeyCode::I
+            | VirtualKeyCode::J
+            | VirtualKeyCode::K
+            | VirtualKeyCode::L
+            | VirtualKeyCode::M
+            | VirtualKeyCode::N
+            | VirtualKeyCode::O
+            | VirtualKeyCode::P
+            | VirtualKeyCode::Q
+            | VirtualKeyCode::R
+            | VirtualKeyCode::S
+            | VirtualKeyCode::T
+            | VirtualKeyCode::U
+            | VirtualKeyCode::V
+            | VirtualKeyCode::W
+            | VirtualKeyCode::X
+            | VirtualKeyCode::Y
+            | VirtualKeyCode::Z
+            | VirtualKeyCode::Caret
+            | VirtualKeyCode::Numpad0
+            | VirtualKeyCode::Numpad1
+            | VirtualKeyCode::Numpad2
+            | VirtualKeyCode::Numpad3
+            | VirtualKeyCode::Numpad4
+            | VirtualKeyCode::Numpad5
+            | VirtualKeyCode::Numpad6
+            | VirtualKeyCode::Numpad7
+            | VirtualKeyCode::Numpad8
+            | VirtualKeyCode::Numpad9
+            | VirtualKeyCode::NumpadAdd
+            | VirtualKeyCode::NumpadDivide
+            | VirtualKeyCode::NumpadDecimal
+            | VirtualKeyCode::NumpadComma
+            | VirtualKeyCode::NumpadEquals
+            | VirtualKeyCode::NumpadMultiply
+            | VirtualKeyCode::NumpadSubtract
+            | VirtualKeyCode::AbntC1
+            | VirtualKeyCode::AbntC2
+            | VirtualKeyCode::Apostrophe
+            | VirtualKeyCode::Asterisk
+            | VirtualKeyCode::At
+            | VirtualKeyCode::Backslash
+            | VirtualKeyCode::Colon
+            | VirtualKeyCode::Comma
+            | VirtualKeyCode::Equals
+            | VirtualKeyCode::Grave
+            | VirtualKeyCode::LBracket
+            | VirtualKeyCode::Minus
+            | VirtualKeyCode::OEM102
+            | VirtualKeyCode::Period
+            | VirtualKeyCode::Plus
+            | VirtualKeyCode::RBracket
+            | VirtualKeyCode::Semicolon
+            | VirtualKeyCode::Slash
+            | VirtualKeyCode::Underline
+            | VirtualKeyCode::Yen
+            | VirtualKeyCode::Unlabeled
+    )
+}
+
+/// The character a key represents without the effect of Shift, Caps Lock and Ctrl,
+/// derived from the text it produced. Returns None for other control characters.
+fn glutin_unmodified_character(
+    character: char,
+    modifiers: &GlutinEventModifiersState,
+) -> Option<char> {
+    let character = match character as u32 {
+        // Ctrl with a letter produces the control character at the position of the letter
+        code @ 0x01..=0x1a if modifiers.ctrl => char::from_u32('a' as u32 + code - 1)?,
+        _ => character,
+    };
+    if character.is_control() {
+        return None;
+    }
+    if modifiers.shift || modifiers.caps_lock {
+        let mut lowercase = character.to_lowercase();
+        if let (Some(lowercase), None) = (lowercase.next(), lowercase.next()) {
+            return Some(lowercase);
+        }
+    }
+    Some(character)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum GlutinEventKeyLocation {
+    /// The key is in its "normal" location on the keyboard
+    Standard,
+    /// The key is on the left side of the keyboard, such as left shift
+    Left,
+    /// The key is on the right side of the keyboard, such as right shift
+    Right,
+    /// The key is on the numpad
+    Numpad,
+}
+
+impl Default for GlutinEventKeyLocation {
+    fn default() -> Self {
+        GlutinEventKeyLocation::Standard
+    }
+}
+
+impl From<VirtualKeyCode> for GlutinEventKeyLocation {
+    fn from(code: VirtualKeyCode) -> Self {
+        match code {
+            VirtualKeyCode::LShift
+            | VirtualKeyCode::LControl
+            | VirtualKeyCode::LAlt
+            | VirtualKeyCode::LWin => GlutinEventKeyLocation::Left,
+            VirtualKeyCode::RShift
+            | VirtualKeyCode::RControl
+            | VirtualKeyCode::RAlt
+            | VirtualKeyCode::RWin => GlutinEventKeyLocation::Right,
+            VirtualKeyCode::Numpad0
+            | VirtualKeyCode::Numpad1
+            | VirtualKeyCode::Numpad2
+            | VirtualKeyCode::Numpad3
+            | VirtualKeyCode::Numpad4
+            | VirtualKeyCode::Numpad5
+            | VirtualKeyCode::Numpad6
+            | VirtualKeyCode::Numpad7
+            | VirtualKeyCode::Numpad8
+            | VirtualKeyCode::Numpad9
+            | VirtualKeyCode::NumpadAdd
+            | VirtualKeyCode::NumpadDivide
+            | VirtualKeyCode::NumpadDecimal
+            | VirtualKeyCode::NumpadComma
+            | VirtualKeyCode::NumpadEnter
+            | VirtualKeyCode::NumpadEquals
+            | VirtualKeyCode::NumpadMultiply
+            | VirtualKeyCode::NumpadSubtract => GlutinEventKeyLocation::Numpad,
+            _ => GlutinEventKeyLocation::Standard,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum GlutinEventTouchForceType {
+    None,
+    Calibrated,
+    Normalized,
+}
+
+impl Default for GlutinEventTouchForceType {
+    fn default() -> Self {
+        GlutinEventTouchForceType::None
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(u32)]
 pub enum GlutinEventMouseScrollDeltaType {
     Unknown,
@@ -270,6 +935,9 @@ pub enum GlutinControlFlow {
     /// `control_flow` cannot be changed from `Exit`, and any future attempts to do so will result
     /// in the `control_flow` parameter being reset to `Exit`.
     Exit,
+    /// When the current loop iteration finishes, suspend the thread until either another event
+    /// arrives or the `wait_timeout` of the event passes.
+    WaitUntil,
 }
 
 impl Default for GlutinEventInputElementState {
@@ -278,24 +946,89 @@ impl Default for GlutinEventInputElementState {
     }
 }
 
+impl From<ElementState> for GlutinEventInputElementState {
+    fn from(state: ElementState) -> Self {
+        match state {
+            ElementState::Pressed => GlutinEventInputElementState::Pressed,
+            ElementState::Released => GlutinEventInputElementState::Released,
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////// E V E N T S ////////////////////////////////////
 ///////////////////////////////////////////////////////////////////////////////////////
 
+/// The message a panic was raised with
+pub fn glutin_panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Unknown panic")
+    }
+}
+
 pub struct EventProcessor {
     pub key_buffer: HashMap<ScanCode, VirtualKeyCode>,
+    pub device_events_enabled: bool,
+    /// The modifiers state attached to keyboard and mouse events
+    pub modifiers: GlutinEventModifiersState,
+    /// Reads the lock keys when the keyboard state may have changed, if the windowing
+    /// system supports it
+    pub lock_keys_query: Option<GlutinLockKeysQuery>,
+    /// Reads the logical character of a key from the keyboard layout, if the windowing
+    /// system supports it
+    pub logical_character_query: Option<GlutinLogicalCharacterQuery>,
+    /// Times of events are measured relative to the epoch
+    pub epoch: Instant,
 }
 
 impl EventProcessor {
     pub fn new() -> Self {
         Self {
             key_buffer: HashMap::new(),
+            device_events_enabled: false,
+            modifiers: Default::default(),
+            lock_keys_query: None,
+            logical_character_query: None,
+            epoch: Instant::now(),
+        }
+    }
+
+    fn refresh_lock_keys(&mut self) {
+        if let Some(lock_keys) = self.lock_keys_query.as_ref().and_then(|query| query()) {
+            self.modifiers.update_lock_keys(lock_keys);
+        }
+    }
+
+    /// Translate an event like `process`, but catch panics during the translation
+    /// and report them as an `Error` event instead of unwinding into the host.
+    pub fn process_safely(
+        &mut self,
+        global_event: glutin::event::Event<GlutinLoopEvent>,
+        c_event: &mut GlutinEvent,
+    ) -> bool {
+        let result = catch_unwind(AssertUnwindSafe(|| self.process(global_event, c_event)));
+        match result {
+            Ok(processed) => processed,
+            Err(panic) => {
+                let message = glutin_panic_message(panic.as_ref());
+                error!("Failed to process an event: {}", message);
+                *c_event = GlutinEvent::error(message, c_event.wait_timeout);
+                true
+            }
         }
     }
 
+    fn micros_since_epoch(&self, instant: Instant) -> u64 {
+        instant.saturating_duration_since(self.epoch).as_micros() as u64
+    }
+
     pub fn process(
         &mut self,
-        global_event: glutin::event::Event<GlutinCustomEvent>,
+        global_event: glutin::event::Event<GlutinLoopEvent>,
         c_event: &mut GlutinEvent,
     ) -> bool {
         c_event.event_type = GlutinEventType::Unknown;
@@ -324,6 +1057,10 @@ impl EventProcessor {
                     WindowEvent::Focused(is_focused) => {
                         c_event.event_type = GlutinEventType::WindowEventFocused;
                         c_event.window_focused.is_focused = is_focused;
+                        // the locks may have been toggled while another window had focus
+                        if is_focused {
+                            self.refresh_lock_keys();
+                        }
                     }
                     WindowEvent::Moved(PhysicalPosition { x, y }) => {
                         c_event.event_type = GlutinEventType::WindowEventMoved;
@@ -340,10 +1077,32 @@ impl EventProcessor {
                         device_id,
                         phase,
                         location,
-                        force: _,
+                        force,
                         id,
                     }) => {
-                        glutin_event_loop_process_touch(c_event, device_id, phase, location, id);
+                        glutin_event_loop_process_touch(
+                            c_event, device_id, phase, location, force, id,
+                        );
+                    }
+                    WindowEvent::TouchpadPressure {
+                        device_id,
+                        pressure,
+                        stage,
+                    } => {
+                        c_event.event_type = GlutinEventType::WindowEventTouchpadPressure;
+                        c_event.touchpad_pressure.device_id = glutin_convert_device_id(device_id);
+                        c_event.touchpad_pressure.pressure = pressure;
+                        c_event.touchpad_pressure.stage = stage;
+                    }
+                    WindowEvent::AxisMotion {
+                        device_id,
+                        axis,
+                        value,
+                    } => {
+                        c_event.event_type = GlutinEventType::WindowEventAxisMotion;
+                        c_event.axis_motion.device_id = glutin_convert_device_id(device_id);
+                        c_event.axis_motion.axis = axis;
+                        c_event.axis_motion.value = value;
                     }
                     WindowEvent::MouseInput {
                         device_id,
@@ -352,6 +1111,7 @@ impl EventProcessor {
                         ..
                     } => {
                         glutin_event_loop_process_mouse_input(c_event, device_id, state, button);
+                        c_event.mouse_input.modifiers = self.modifiers;
                     }
                     WindowEvent::CursorMoved {
                         device_id,
@@ -359,6 +1119,7 @@ impl EventProcessor {
                         ..
                     } => {
                         glutin_event_loop_process_cursor_moved(c_event, device_id, position);
+                        c_event.cursor_moved.modifiers = self.modifiers;
                     }
                     WindowEvent::CursorEntered { device_id } => {
                         glutin_event_loop_process_cursor_entered(c_event, device_id);
@@ -373,6 +1134,7 @@ impl EventProcessor {
                         ..
                     } => {
                         glutin_event_loop_process_mouse_wheel(c_event, device_id, delta, phase);
+                        c_event.mouse_wheel.modifiers = self.modifiers;
                     }
                     WindowEvent::KeyboardInput {
                         device_id,
@@ -384,19 +1146,39 @@ impl EventProcessor {
                     WindowEvent::ReceivedCharacter(character) => {
                         glutin_event_loop_process_received_character(c_event, character);
                     }
+                    WindowEvent::DroppedFile(path) => {
+                        glutin_event_loop_process_file(
+                            c_event,
+                            GlutinEventType::WindowEventDroppedFile,
+                            Some(path),
+                        );
+                    }
+                    WindowEvent::HoveredFile(path) => {
+                        glutin_event_loop_process_file(
+                            c_event,
+                            GlutinEventType::WindowEventHoveredFile,
+                            Some(path),
+                        );
+                    }
+                    WindowEvent::HoveredFileCancelled => {
+                        glutin_event_loop_process_file(
+                            c_event,
+                            GlutinEventType::WindowEventHoveredFileCancelled,
+                            None,
+                        );
+                    }
                     WindowEvent::ModifiersChanged(modifiers) => {
                         c_event.event_type = GlutinEventType::ModifiersChanged;
-                        c_event.modifiers.alt = modifiers.alt();
-                        c_event.modifiers.ctrl = modifiers.ctrl();
-                        c_event.modifiers.logo = modifiers.logo();
-                        c_event.modifiers.shift = modifiers.shift();
+                        self.modifiers.update_from_modifiers(modifiers);
+                        self.refresh_lock_keys();
+                        c_event.modifiers = self.modifiers;
                     }
                     _ => result = false,
                 }
             }
 
-            glutin::event::Event::NewEvents(_start_cause) => {
-                c_event.event_type = GlutinEventType::NewEvents;
+            glutin::event::Event::NewEvents(start_cause) => {
+                self.process_new_events(c_event, start_cause);
             }
             glutin::event::Event::MainEventsCleared => {
                 c_event.event_type = GlutinEventType::MainEventsCleared;
@@ -418,18 +1200,61 @@ impl EventProcessor {
             glutin::event::Event::Resumed => {
                 c_event.event_type = GlutinEventType::Resumed;
             }
-            glutin::event::Event::UserEvent(custom_event) => {
+            glutin::event::Event::UserEvent(GlutinLoopEvent::Custom(custom_event)) => {
                 c_event.event_type = GlutinEventType::UserEvent;
                 c_event.user_event.event = custom_event;
             }
-            Event::DeviceEvent {
-                device_id: _,
-                event: _,
-            } => result = false,
+            // performed by the event loop, the host never sees it
+            glutin::event::Event::UserEvent(GlutinLoopEvent::CommandsQueued) => {
+                result = false;
+            }
+            Event::DeviceEvent { device_id, event } => {
+                if self.device_events_enabled {
+                    result = glutin_event_loop_process_device_event(c_event, device_id, event);
+                } else {
+                    result = false;
+                }
+            }
         }
         result
     }
 
+    fn process_new_events(&self, c_event: &mut GlutinEvent, start_cause: StartCause) {
+        c_event.event_type = GlutinEventType::NewEvents;
+        c_event.new_events.now = self.micros_since_epoch(Instant::now());
+
+        match start_cause {
+            StartCause::ResumeTimeReached {
+                start,
+                requested_resume,
+            } => {
+                c_event.new_events.cause = GlutinEventStartCause::ResumeTimeReached;
+                c_event.new_events.start = self.micros_since_epoch(start);
+                c_event.new_events.has_requested_resume = true;
+                c_event.new_events.requested_resume = self.micros_since_epoch(requested_resume);
+            }
+            StartCause::WaitCancelled {
+                start,
+                requested_resume,
+            } => {
+                c_event.new_events.cause = GlutinEventStartCause::WaitCancelled;
+                c_event.new_events.start = self.micros_since_epoch(start);
+                c_event.new_events.has_requested_resume = requested_resume.is_some();
+                if let Some(requested_resume) = requested_resume {
+                    c_event.new_events.requested_resume = self.micros_since_epoch(requested_resume);
+                }
+            }
+            StartCause::Poll => {
+                c_event.new_events.cause = GlutinEventStartCause::Poll;
+                c_event.new_events.start = c_event.new_events.now;
+            }
+            StartCause::Init => {
+                c_event.new_events.cause = GlutinEventStartCause::Init;
+                c_event.new_events.start = c_event.new_events.now;
+            }
+        }
+    }
+
     fn process_keyboard_input(
         &mut self,
         c_event: &mut GlutinEvent,
@@ -438,10 +1263,18 @@ impl EventProcessor {
         is_synthetic: bool,
     ) {
         c_event.event_type = GlutinEventType::WindowEventKeyboardInput;
-        c_event.keyboard_input.device_id = unsafe { transmute(&device_id) };
+        c_event.keyboard_input.device_id = glutin_convert_device_id(device_id);
         c_event.keyboard_input.is_synthetic = is_synthetic;
         c_event.keyboard_input.scan_code = input.scancode;
 
+        if self
+            .modifiers
+            .update_from_key(input.scancode, input.virtual_keycode, input.state)
+        {
+            self.refresh_lock_keys();
+        }
+        c_event.keyboard_input.modifiers = self.modifiers;
+
         match input.state {
             ElementState::Pressed => {
                 c_event.keyboard_input.state = GlutinEventInputElementState::Pressed;
@@ -472,11 +1305,28 @@ impl EventProcessor {
             Some(code) => {
                 c_event.keyboard_input.has_virtual_keycode = true;
                 c_event.keyboard_input.virtual_keycode = code;
+                c_event.keyboard_input.location = code.into();
             }
             None => {
                 c_event.keyboard_input.has_virtual_keycode = false;
+                c_event.keyboard_input.location = GlutinEventKeyLocation::Standard;
             }
         }
+
+        // keys without a layout character are identified later by the text they produce,
+        // see `GlutinEvent::attach_key_text`
+        let layout_character = self
+            .logical_character_query
+            .as_ref()
+            .and_then(|query| query(input.scancode));
+        if matches!(key_code, Some(code) if glutin_is_named_key(code)) {
+            c_event.keyboard_input.logical_key = GlutinEventLogicalKey::Named;
+        } else if let Some(character) = layout_character {
+            c_event.keyboard_input.logical_key = GlutinEventLogicalKey::Character;
+            c_event.keyboard_input.logical_character = character as u32;
+        } else {
+            c_event.keyboard_input.logical_key = GlutinEventLogicalKey::Unidentified;
+        }
     }
 }
 
@@ -487,7 +1337,7 @@ fn glutin_event_loop_process_mouse_wheel(
     phase: TouchPhase,
 ) {
     c_event.event_type = GlutinEventType::WindowEventMouseWheel;
-    c_event.mouse_wheel.device_id = unsafe { transmute(&device_id) };
+    c_event.mouse_wheel.device_id = glutin_convert_device_id(device_id);
 
     match delta {
         MouseScrollDelta::LineDelta(x, y) => {
@@ -523,14 +1373,38 @@ fn glutin_event_loop_process_touch(
     device_id: DeviceId,
     phase: TouchPhase,
     location: PhysicalPosition<f64>,
+    force: Option<Force>,
     id: u64,
 ) {
     c_event.event_type = GlutinEventType::WindowEventTouch;
-    c_event.touch.device_id = unsafe { transmute(&device_id) };
+    c_event.touch.device_id = glutin_convert_device_id(device_id);
     c_event.touch.x = location.x;
     c_event.touch.y = location.y;
     c_event.touch.id = id;
 
+    match force {
+        None => {
+            c_event.touch.force_type = GlutinEventTouchForceType::None;
+        }
+        Some(Force::Calibrated {
+            force,
+            max_possible_force,
+            altitude_angle,
+        }) => {
+            c_event.touch.force_type = GlutinEventTouchForceType::Calibrated;
+            c_event.touch.force = force;
+            c_event.touch.max_possible_force = max_possible_force;
+            c_event.touch.has_altitude_angle = altitude_angle.is_some();
+            c_event.touch.altitude_angle = altitude_angle.unwrap_or_default();
+        }
+        Some(Force::Normalized(_)) => {
+            c_event.touch.force_type = GlutinEventTouchForceType::Normalized;
+        }
+    }
+    if let Some(force) = force {
+        c_event.touch.normalized_force = force.normalized();
+    }
+
     match phase {
         TouchPhase::Started => {
             c_event.touch.phase = GlutinEventTouchPhase::Started;
@@ -554,7 +1428,7 @@ fn glutin_event_loop_process_mouse_input(
     button: MouseButton,
 ) {
     c_event.event_type = GlutinEventType::WindowEventMouseInput;
-    c_event.mouse_input.device_id = unsafe { transmute(&device_id) };
+    c_event.mouse_input.device_id = glutin_convert_device_id(device_id);
 
     match state {
         ElementState::Released => {
@@ -591,7 +1465,7 @@ fn glutin_event_loop_process_cursor_moved<T: Into<f64>>(
     position: PhysicalPosition<T>,
 ) {
     c_event.event_type = GlutinEventType::WindowEventCursorMoved;
-    c_event.cursor_moved.device_id = unsafe { transmute(&device_id) };
+    c_event.cursor_moved.device_id = glutin_convert_device_id(device_id);
 
     c_event.cursor_moved.x = position.x.into();
     c_event.cursor_moved.y = position.y.into();
@@ -628,6 +1502,74 @@ fn glutin_event_loop_process_received_character(c_event: &mut GlutinEvent, chara
     if length >= 4 {
         c_event.received_character.byte_4 = bytes[3];
     }
+
+    c_event.text.set_string(character.to_string());
+}
+
+fn glutin_event_loop_process_device_event(
+    c_event: &mut GlutinEvent,
+    device_id: DeviceId,
+    event: DeviceEvent,
+) -> bool {
+    let device_id: i64 = glutin_convert_device_id(device_id);
+
+    match event {
+        DeviceEvent::MouseMotion { delta } => {
+            c_event.event_type = GlutinEventType::DeviceEventMouseMotion;
+            c_event.device_motion.device_id = device_id;
+            c_event.device_motion.delta_x = delta.0;
+            c_event.device_motion.delta_y = delta.1;
+        }
+        DeviceEvent::Motion { axis, value } => {
+            c_event.event_type = GlutinEventType::DeviceEventMotion;
+            c_event.device_axis.device_id = device_id;
+            c_event.device_axis.axis = axis;
+            c_event.device_axis.value = value;
+        }
+        DeviceEvent::Button { button, state } => {
+            c_event.event_type = GlutinEventType::DeviceEventButton;
+            c_event.device_button.device_id = device_id;
+            c_event.device_button.button = button;
+            c_event.device_button.state = state.into();
+        }
+        DeviceEvent::Key(input) => {
+            c_event.event_type = GlutinEventType::DeviceEventKey;
+            c_event.device_key.device_id = device_id;
+            c_event.device_key.scan_code = input.scancode;
+            c_event.device_key.state = input.state.into();
+            match input.virtual_keycode {
+                Some(code) => {
+                    c_event.device_key.has_virtual_keycode = true;
+                    c_event.device_key.virtual_keycode = code;
+                }
+                None => {
+                    c_event.device_key.has_virtual_keycode = false;
+                }
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn glutin_event_loop_process_file(
+    c_event: &mut GlutinEvent,
+    event_type: GlutinEventType,
+    path: Option<PathBuf>,
+) {
+    c_event.event_type = event_type;
+    if let Some(path) = path {
+        c_event.text.set_string(path.to_string_lossy().to_string());
+    }
+}
+
+/// Return true if the event carries a text payload
+#[no_mangle]
+pub extern "C" fn glutin_event_has_text(ptr: *mut GlutinEvent) -> bool {
+    if ptr.is_null() {
+        return false;
+    }
+    unsafe { &*ptr }.text.has_string()
 }
 
 #[no_mangle]
@@ -637,3 +1579,1009 @@ pub extern "C" fn glutin_event_drop(ptr: *mut GlutinEvent) {
     }
     unsafe { Box::from_raw(ptr) };
 }
+
+///////////////////////////////////////////////////////////////////////////////////////
+///////////////////////////////////// L A Y O U T /////////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////
+
+/// Describe the layout of the event structs for the FFI manifest
+pub(crate) fn glutin_event_struct_layouts() -> Vec<FfiStruct> {
+    vec![
+        crate::ffi_struct!(GlutinEvent {
+            window_id,
+            event_type,
+            touch,
+            mouse_wheel,
+            mouse_input,
+            cursor_moved,
+            keyboard_input,
+            received_character,
+            window_resized,
+            scale_factor,
+            window_moved,
+            window_focused,
+            modifiers,
+            user_event,
+            text,
+            device_motion,
+            device_axis,
+            device_button,
+            device_key,
+            touchpad_pressure,
+            axis_motion,
+            new_events,
+            wait_timeout,
+            is_synthetic
+        }),
+        crate::ffi_struct!(GlutinTouchEvent {
+            device_id,
+            phase,
+            x,
+            y,
+            id,
+            force_type,
+            normalized_force,
+            force,
+            max_possible_force,
+            has_altitude_angle,
+            altitude_angle
+        }),
+        crate::ffi_struct!(GlutinTouchpadPressureEvent {
+            device_id,
+            pressure,
+            stage
+        }),
+        crate::ffi_struct!(GlutinAxisMotionEvent {
+            device_id,
+            axis,
+            value
+        }),
+        crate::ffi_struct!(GlutinMouseWheelEvent {
+            device_id,
+            phase,
+            delta,
+            modifiers
+        }),
+        crate::ffi_struct!(GlutinMouseInputEvent {
+            device_id,
+            state,
+            button,
+            modifiers
+        }),
+        crate::ffi_struct!(GlutinCursorMovedEvent {
+            device_id,
+            x,
+            y,
+            modifiers
+        }),
+        crate::ffi_struct!(GlutinWindowResizedEvent { width, height }),
+        crate::ffi_struct!(GlutinWindowScaleFactorChangedEvent {
+            scale_factor,
+            width,
+            height
+        }),
+        crate::ffi_struct!(GlutinWindowMovedEvent { x, y }),
+        crate::ffi_struct!(GlutinWindowFocusedEvent { is_focused }),
+        crate::ffi_struct!(GlutinEventKeyboardInput {
+            device_id,
+            scan_code,
+            state,
+            has_virtual_keycode,
+            virtual_keycode,
+            is_synthetic,
+            modifiers,
+            location,
+            logical_key,
+            logical_character
+        }),
+        crate::ffi_struct!(GlutinEventReceivedCharacter {
+            length,
+            byte_1,
+            byte_2,
+            byte_3,
+            byte_4
+        }),
+        crate::ffi_struct!(GlutinMouseScrollDelta { delta_type, x, y }),
+        crate::ffi_struct!(GlutinEventModifiersState {
+            shift,
+            ctrl,
+            alt,
+            logo,
+            left_shift,
+            right_shift,
+            left_ctrl,
+            right_ctrl,
+            left_alt,
+            right_alt,
+            left_logo,
+            right_logo,
+            caps_lock,
+            num_lock
+        }),
+        crate::ffi_struct!(GlutinEventMouseButton {
+            button_type,
+            button_code
+        }),
+        crate::ffi_struct!(GlutinEventUserEvent { event }),
+        crate::ffi_struct!(GlutinEventText { string }),
+        crate::ffi_struct!(GlutinNewEventsEvent {
+            cause,
+            start,
+            has_requested_resume,
+            requested_resume,
+            now
+        }),
+        crate::ffi_struct!(GlutinDeviceMotionEvent {
+            device_id,
+            delta_x,
+            delta_y
+        }),
+        crate::ffi_struct!(GlutinDeviceAxisEvent {
+            device_id,
+            axis,
+            value
+        }),
+        crate::ffi_struct!(GlutinDeviceButtonEvent {
+            device_id,
+            button,
+            state
+        }),
+        crate::ffi_struct!(GlutinDeviceKeyEvent {
+            device_id,
+            scan_code,
+            state,
+            has_virtual_keycode,
+            virtual_keycode
+        }),
+    ]
+}
+
+pub(crate) fn glutin_event_enum_layouts() -> Vec<FfiEnum> {
+    vec![
+        crate::ffi_enum!(GlutinEventMouseButtonType {
+            Unknown,
+            Left,
+            Right,
+            Middle,
+            Other
+        }),
+        crate::ffi_enum!(GlutinEventType {
+            Unknown,
+            WindowEventResized,
+            WindowEventMoved,
+            WindowEventCloseRequested,
+            WindowEventDestroyed,
+            WindowEventDroppedFile,
+            WindowEventHoveredFile,
+            WindowEventHoveredFileCancelled,
+            WindowEventReceivedCharacter,
+            WindowEventFocused,
+            WindowEventKeyboardInput,
+            WindowEventCursorMoved,
+            WindowEventCursorEntered,
+            WindowEventCursorLeft,
+            WindowEventMouseWheel,
+            WindowEventMouseInput,
+            WindowEventTouchpadPressure,
+            WindowEventAxisMotion,
+            WindowEventTouch,
+            WindowEventScaleFactorChanged,
+            NewEvents,
+            MainEventsCleared,
+            LoopDestroyed,
+            Suspended,
+            Resumed,
+            RedrawRequested,
+            RedrawEventsCleared,
+            ModifiersChanged,
+            UserEvent,
+            DeviceEventMouseMotion,
+            DeviceEventMotion,
+            DeviceEventButton,
+            DeviceEventKey,
+            Error
+        }),
+        crate::ffi_enum!(GlutinEventTouchPhase {
+            Unknown,
+            Started,
+            Moved,
+            Ended,
+            Cancelled
+        }),
+        crate::ffi_enum!(GlutinEventStartCause {
+            Unknown,
+            Init,
+            Poll,
+            WaitCancelled,
+            ResumeTimeReached
+        }),
+        crate::ffi_enum!(GlutinEventLogicalKey {
+            Unidentified,
+            Named,
+            Character
+        }),
+        crate::ffi_enum!(GlutinEventKeyLocation {
+            Standard,
+            Left,
+            Right,
+            Numpad
+        }),
+        crate::ffi_enum!(GlutinEventTouchForceType {
+            None,
+            Calibrated,
+            Normalized
+        }),
+        crate::ffi_enum!(GlutinEventMouseScrollDeltaType {
+            Unknown,
+            LineDelta,
+            PixelDelta
+        }),
+        crate::ffi_enum!(GlutinEventInputElementState {
+            Unknown,
+            Pressed,
+            Released
+        }),
+        crate::ffi_enum!(GlutinControlFlow {
+            Poll,
+            Wait,
+            Exit,
+            WaitUntil
+        }),
+    ]
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////// R E C O R D I N G //////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////
+
+crate::recorded_struct!(GlutinEvent {
+    window_id,
+    event_type,
+    touch,
+    mouse_wheel,
+    mouse_input,
+    cursor_moved,
+    keyboard_input,
+    received_character,
+    window_resized,
+    scale_factor,
+    window_moved,
+    window_focused,
+    modifiers,
+    user_event,
+    text,
+    device_motion,
+    device_axis,
+    device_button,
+    device_key,
+    touchpad_pressure,
+    axis_motion,
+    new_events,
+    wait_timeout,
+    is_synthetic
+});
+
+crate::recorded_struct!(GlutinTouchEvent {
+    device_id,
+    phase,
+    x,
+    y,
+    id,
+    force_type,
+    normalized_force,
+    force,
+    max_possible_force,
+    has_altitude_angle,
+    altitude_angle
+});
+
+crate::recorded_struct!(GlutinTouchpadPressureEvent {
+    device_id,
+    pressure,
+    stage
+});
+
+crate::recorded_struct!(GlutinAxisMotionEvent {
+    device_id,
+    axis,
+    value
+});
+
+crate::recorded_struct!(GlutinMouseWheelEvent {
+    device_id,
+    phase,
+    delta,
+    modifiers
+});
+
+crate::recorded_struct!(GlutinMouseInputEvent {
+    device_id,
+    state,
+    button,
+    modifiers
+});
+
+crate::recorded_struct!(GlutinCursorMovedEvent {
+    device_id,
+    x,
+    y,
+    modifiers
+});
+
+crate::recorded_struct!(GlutinWindowResizedEvent { width, height });
+
+crate::recorded_struct!(GlutinWindowScaleFactorChangedEvent {
+    scale_factor,
+    width,
+    height
+});
+
+crate::recorded_struct!(GlutinWindowMovedEvent { x, y });
+
+crate::recorded_struct!(GlutinWindowFocusedEvent { is_focused });
+
+crate::recorded_struct!(GlutinEventKeyboardInput {
+    device_id,
+    scan_code,
+    state,
+    has_virtual_keycode,
+    virtual_keycode,
+    is_synthetic,
+    modifiers,
+    location,
+    logical_key,
+    logical_character
+});
+
+crate::recorded_struct!(GlutinEventReceivedCharacter {
+    length,
+    byte_1,
+    byte_2,
+    byte_3,
+    byte_4
+});
+
+crate::recorded_struct!(GlutinMouseScrollDelta { delta_type, x, y });
+
+crate::recorded_struct!(GlutinEventModifiersState {
+    shift,
+    ctrl,
+    alt,
+    logo,
+    left_shift,
+    right_shift,
+    left_ctrl,
+    right_ctrl,
+    left_alt,
+    right_alt,
+    left_logo,
+    right_logo,
+    caps_lock,
+    num_lock
+});
+
+crate::recorded_struct!(GlutinEventMouseButton {
+    button_type,
+    button_code
+});
+
+crate::recorded_struct!(GlutinEventUserEvent { event });
+
+crate::recorded_struct!(GlutinNewEventsEvent {
+    cause,
+    start,
+    has_requested_resume,
+    requested_resume,
+    now
+});
+
+crate::recorded_struct!(GlutinDeviceMotionEvent {
+    device_id,
+    delta_x,
+    delta_y
+});
+
+crate::recorded_struct!(GlutinDeviceAxisEvent {
+    device_id,
+    axis,
+    value
+});
+
+crate::recorded_struct!(GlutinDeviceButtonEvent {
+    device_id,
+    button,
+    state
+});
+
+crate::recorded_struct!(GlutinDeviceKeyEvent {
+    device_id,
+    scan_code,
+    state,
+    has_virtual_keycode,
+    virtual_keycode
+});
+
+crate::recorded_enum!(GlutinEventMouseButtonType {
+    Unknown,
+    Left,
+    Right,
+    Middle,
+    Other
+});
+
+crate::recorded_enum!(GlutinEventType {
+    Unknown,
+    WindowEventResized,
+    WindowEventMoved,
+    WindowEventCloseRequested,
+    WindowEventDestroyed,
+    WindowEventDroppedFile,
+    WindowEventHoveredFile,
+    WindowEventHoveredFileCancelled,
+    WindowEventReceivedCharacter,
+    WindowEventFocused,
+    WindowEventKeyboardInput,
+    WindowEventCursorMoved,
+    WindowEventCursorEntered,
+    WindowEventCursorLeft,
+    WindowEventMouseWheel,
+    WindowEventMouseInput,
+    WindowEventTouchpadPressure,
+    WindowEventAxisMotion,
+    WindowEventTouch,
+    WindowEventScaleFactorChanged,
+    NewEvents,
+    MainEventsCleared,
+    LoopDestroyed,
+    Suspended,
+    Resumed,
+    RedrawRequested,
+    RedrawEventsCleared,
+    ModifiersChanged,
+    UserEvent,
+    DeviceEventMouseMotion,
+    DeviceEventMotion,
+    DeviceEventButton,
+    DeviceEventKey,
+    Error
+});
+
+crate::recorded_enum!(GlutinEventTouchPhase {
+    Unknown,
+    Started,
+    Moved,
+    Ended,
+    Cancelled
+});
+
+crate::recorded_enum!(GlutinEventStartCause {
+    Unknown,
+    Init,
+    Poll,
+    WaitCancelled,
+    ResumeTimeReached
+});
+
+crate::recorded_enum!(GlutinEventLogicalKey {
+    Unidentified,
+    Named,
+    Character
+});
+
+crate::recorded_enum!(GlutinEventKeyLocation {
+    Standard,
+    Left,
+    Right,
+    Numpad
+});
+
+crate::recorded_enum!(GlutinEventTouchForceType {
+    None,
+    Calibrated,
+    Normalized
+});
+
+crate::recorded_enum!(GlutinEventMouseScrollDeltaType {
+    Unknown,
+    LineDelta,
+    PixelDelta
+});
+
+crate::recorded_enum!(GlutinEventInputElementState {
+    Unknown,
+    Pressed,
+    Released
+});
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn device_id() -> DeviceId {
+        unsafe { DeviceId::dummy() }
+    }
+
+    #[test]
+    fn modifier_sides_and_lock_keys_are_tracked_from_key_events() {
+        use ElementState::{Pressed, Released};
+        type Key = (ScanCode, Option<VirtualKeyCode>, ElementState);
+
+        let none = GlutinEventModifiersState::default();
+        let left_shift = (42, Some(VirtualKeyCode::LShift));
+        let right_shift = (54, Some(VirtualKeyCode::RShift));
+        let right_ctrl = (97, Some(VirtualKeyCode::RControl));
+        let caps_lock = (58, Some(VirtualKeyCode::Capital));
+        let key_a = (30, Some(VirtualKeyCode::A));
+
+        let cases: Vec<(Vec<Key>, GlutinEventModifiersState, bool)> = vec![
+            (
+                vec![(left_shift.0, left_shift.1, Pressed)],
+                GlutinEventModifiersState {
+                    shift: true,
+                    left_shift: true,
+                    ..none
+                },
+                false,
+            ),
+            (
+                vec![
+                    (left_shift.0, left_shift.1, Pressed),
+                    (right_shift.0, right_shift.1, Pressed),
+                    (left_shift.0, left_shift.1, Released),
+                ],
+                GlutinEventModifiersState {
+                    shift: true,
+                    right_shift: true,
+                    ..none
+                },
+                false,
+            ),
+            (
+                vec![
+                    (right_ctrl.0, right_ctrl.1, Pressed),
+                    (right_ctrl.0, right_ctrl.1, Released),
+                ],
+                none,
+                false,
+            ),
+            (
+                vec![(caps_lock.0, caps_lock.1, Pressed)],
+                GlutinEventModifiersState {
+                    caps_lock: true,
+                    ..none
+                },
+                true,
+            ),
+            (
+                vec![
+                    (caps_lock.0, caps_lock.1, Pressed),
+                    (caps_lock.0, caps_lock.1, Released),
+                    (caps_lock.0, caps_lock.1, Pressed),
+                ],
+                none,
+                true,
+            ),
+            (vec![(key_a.0, key_a.1, Pressed)], none, false),
+        ];
+
+        for (keys, expected, is_lock_key) in cases {
+            let mut modifiers = GlutinEventModifiersState::default();
+            let mut last_is_lock_key = false;
+            for (scan_code, virtual_keycode, state) in keys {
+                last_is_lock_key = modifiers.update_from_key(scan_code, virtual_keycode, state);
+            }
+            assert_eq!(modifiers, expected);
+            assert_eq!(last_is_lock_key, is_lock_key);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn alt_gr_without_virtual_keycode_survives_modifier_changes() {
+        let mut modifiers = GlutinEventModifiersState::default();
+        // X11 reports AltGr only by its scan code
+        modifiers.update_from_key(100, None, ElementState::Pressed);
+        assert!(modifiers.right_alt && modifiers.alt);
+
+        // some platforms do not report AltGr as alt in the modifiers
+        modifiers.update_from_modifiers(ModifiersState::empty());
+        assert!(modifiers.right_alt);
+        assert!(!modifiers.alt);
+
+        modifiers.update_from_key(100, None, ElementState::Released);
+        assert!(!modifiers.right_alt && !modifiers.alt);
+    }
+
+    #[test]
+    fn unmodified_character_undoes_shift_caps_lock_and_ctrl() {
+        let none = GlutinEventModifiersState::default();
+        let shift = GlutinEventModifiersState {
+            shift: true,
+            ..none
+        };
+        let caps_lock = GlutinEventModifiersState {
+            caps_lock: true,
+            ..none
+        };
+        let ctrl = GlutinEventModifiersState { ctrl: true, ..none };
+
+        let cases = [
+            ('a', none, Some('a')),
+            ('A', shift, Some('a')),
+            ('Ä', caps_lock, Some('ä')),
+            ('!', shift, Some('!')),
+            ('\u{1}', ctrl, Some('a')),
+            ('\u{1a}', ctrl, Some('z')),
+            ('\u{1b}', ctrl, None),
+            ('\r', none, None),
+            ('\u{7f}', none, None),
+            ('€', none, Some('€')),
+        ];
+
+        for (character, modifiers, expected) in cases {
+            assert_eq!(
+                glutin_unmodified_character(character, &modifiers),
+                expected,
+                "{:?}",
+                character
+            );
+        }
+    }
+
+    pub(crate) fn key_press(
+        key_code: Option<VirtualKeyCode>,
+        logical_key: GlutinEventLogicalKey,
+    ) -> GlutinEvent {
+        let mut c_event = GlutinEvent {
+            event_type: GlutinEventType::WindowEventKeyboardInput,
+            ..Default::default()
+        };
+        c_event.keyboard_input.scan_code = 16;
+        c_event.keyboard_input.has_virtual_keycode = key_code.is_some();
+        c_event.keyboard_input.virtual_keycode = key_code.unwrap_or(VirtualKeyCode::Unlabeled);
+        c_event.keyboard_input.state = GlutinEventInputElementState::Pressed;
+        c_event.keyboard_input.logical_key = logical_key;
+        c_event
+    }
+
+    pub(crate) fn key_release(key_code: Option<VirtualKeyCode>) -> GlutinEvent {
+        let mut c_event = key_press(key_code, GlutinEventLogicalKey::Unidentified);
+        c_event.keyboard_input.state = GlutinEventInputElementState::Released;
+        c_event
+    }
+
+    pub(crate) fn received_character(text: &str) -> GlutinEvent {
+        let mut c_event = GlutinEvent {
+            event_type: GlutinEventType::WindowEventReceivedCharacter,
+            ..Default::default()
+        };
+        c_event.text.set_string(text.to_string());
+        c_event
+    }
+
+    #[test]
+    fn key_press_is_identified_by_its_text() {
+        use GlutinEventLogicalKey::{Character, Named, Unidentified};
+
+        let cases = [
+            (
+                Some(VirtualKeyCode::Q),
+                Unidentified,
+                "a",
+                Character,
+                'a' as u32,
+            ),
+            (None, Unidentified, "é", Character, 'é' as u32),
+            (Some(VirtualKeyCode::Return), Named, "\r", Named, 0),
+            (
+                Some(VirtualKeyCode::Q),
+                Character,
+                "A",
+                Character,
+                'q' as u32,
+            ),
+            (
+                Some(VirtualKeyCode::Escape),
+                Unidentified,
+                "\u{1b}",
+                Unidentified,
+                0,
+            ),
+        ];
+
+        for (key_code, logical_key, text, expected_key, expected_character) in cases {
+            let mut c_event = key_press(key_code, logical_key);
+            if logical_key == Character {
+                c_event.keyboard_input.logical_character = 'q' as u32;
+            }
+            assert!(c_event.attach_key_text(&received_character(text)));
+            assert_eq!(c_event.text.string().as_deref(), Some(text));
+            assert_eq!(
+                c_event.keyboard_input.logical_key, expected_key,
+                "{:?}",
+                text
+            );
+            assert_eq!(c_event.keyboard_input.logical_character, expected_character);
+        }
+    }
+
+    #[test]
+    fn key_text_is_attached_only_to_a_press_in_the_same_window() {
+        let mut released = key_release(None);
+        assert!(!released.attach_key_text(&received_character("a")));
+
+        let mut press = key_press(None, GlutinEventLogicalKey::Unidentified);
+        let mut other_window = received_character("a");
+        other_window.window_id.low = 1;
+        assert!(!press.attach_key_text(&other_window));
+        assert!(!press.attach_key_text(&key_press(None, GlutinEventLogicalKey::Unidentified)));
+
+        assert!(press.attach_key_text(&received_character("a")));
+        assert!(!press.attach_key_text(&received_character("b")));
+        assert_eq!(press.text.string().as_deref(), Some("a"));
+    }
+
+    pub(crate) fn key_logical_character(c_event: &GlutinEvent) -> (GlutinEventLogicalKey, u32) {
+        let key = &c_event.keyboard_input;
+        (key.logical_key, key.logical_character)
+    }
+
+    #[test]
+    fn key_release_gets_the_character_of_its_press() {
+        let mut characters = HashMap::new();
+        let press = {
+            let mut press = key_press(None, GlutinEventLogicalKey::Unidentified);
+            press.attach_key_text(&received_character("a"));
+            press
+        };
+        let (scan_code, character) = press.key_logical_character().unwrap();
+        characters.insert(scan_code, character);
+
+        let mut release = key_release(None);
+        release.set_released_key_character(&mut characters);
+        assert_eq!(
+            release.keyboard_input.logical_key,
+            GlutinEventLogicalKey::Character
+        );
+        assert_eq!(release.keyboard_input.logical_character, 'a' as u32);
+        assert!(characters.is_empty());
+    }
+
+    #[test]
+    fn character_keys_are_not_named() {
+        let cases = [
+            (VirtualKeyCode::A, false),
+            (VirtualKeyCode::Key1, false),
+            (VirtualKeyCode::NumpadAdd, false),
+            (VirtualKeyCode::Semicolon, false),
+            (VirtualKeyCode::Unlabeled, false),
+            (VirtualKeyCode::Return, true),
+            (VirtualKeyCode::Space, true),
+            (VirtualKeyCode::Left, true),
+            (VirtualKeyCode::F1, true),
+            (VirtualKeyCode::LShift, true),
+        ];
+        for (key_code, is_named) in cases {
+            assert_eq!(glutin_is_named_key(key_code), is_named, "{:?}", key_code);
+        }
+    }
+
+    #[test]
+    fn new_events_report_their_start_cause_relative_to_the_epoch() {
+        use std::time::Duration;
+
+        let processor = EventProcessor::new();
+        let at = |micros: u64| processor.epoch + Duration::from_micros(micros);
+        let before_epoch = processor.epoch.checked_sub(Duration::from_secs(1));
+
+        // the expected start is None if it is the moment of the translation
+        let mut cases = vec![
+            (StartCause::Init, GlutinEventStartCause::Init, None, None),
+            (StartCause::Poll, GlutinEventStartCause::Poll, None, None),
+            (
+                StartCause::ResumeTimeReached {
+                    start: at(10),
+                    requested_resume: at(20),
+                },
+                GlutinEventStartCause::ResumeTimeReached,
+                Some(10),
+                Some(20),
+            ),
+            (
+                StartCause::WaitCancelled {
+                    start: at(30),
+                    requested_resume: Some(at(40)),
+                },
+                GlutinEventStartCause::WaitCancelled,
+                Some(30),
+                Some(40),
+            ),
+            (
+                StartCause::WaitCancelled {
+                    start: at(50),
+                    requested_resume: None,
+                },
+                GlutinEventStartCause::WaitCancelled,
+                Some(50),
+                None,
+            ),
+        ];
+        if let Some(before_epoch) = before_epoch {
+            cases.push((
+                StartCause::WaitCancelled {
+                    start: before_epoch,
+                    requested_resume: None,
+                },
+                GlutinEventStartCause::WaitCancelled,
+                Some(0),
+                None,
+            ));
+        }
+
+        for (start_cause, cause, start, requested_resume) in cases {
+            let mut c_event = GlutinEvent::default();
+            processor.process_new_events(&mut c_event, start_cause);
+            let new_events = &c_event.new_events;
+            assert_eq!(c_event.event_type, GlutinEventType::NewEvents);
+            assert_eq!(new_events.cause, cause);
+            assert_eq!(new_events.start, start.unwrap_or(new_events.now));
+            assert_eq!(new_events.has_requested_resume, requested_resume.is_some());
+            assert_eq!(new_events.requested_resume, requested_resume.unwrap_or(0));
+        }
+    }
+
+    fn cursor_moved(window: u64, device_id: i64, x: f64, y: f64) -> GlutinEvent {
+        let mut c_event = GlutinEvent {
+            event_type: GlutinEventType::WindowEventCursorMoved,
+            ..Default::default()
+        };
+        c_event.window_id.low = window;
+        c_event.cursor_moved.device_id = device_id;
+        c_event.cursor_moved.x = x;
+        c_event.cursor_moved.y = y;
+        c_event
+    }
+
+    fn mouse_wheel(
+        window: u64,
+        phase: GlutinEventTouchPhase,
+        delta_type: GlutinEventMouseScrollDeltaType,
+        x: f64,
+        y: f64,
+    ) -> GlutinEvent {
+        let mut c_event = GlutinEvent {
+            event_type: GlutinEventType::WindowEventMouseWheel,
+            ..Default::default()
+        };
+        c_event.window_id.low = window;
+        c_event.mouse_wheel.phase = phase;
+        c_event.mouse_wheel.delta.delta_type = delta_type;
+        c_event.mouse_wheel.delta.x = x;
+        c_event.mouse_wheel.delta.y = y;
+        c_event
+    }
+
+    #[test]
+    fn only_moves_and_scrolls_of_the_same_device_and_window_coalesce() {
+        use GlutinEventMouseScrollDeltaType::{LineDelta, PixelDelta};
+        use GlutinEventTouchPhase::{Ended, Moved, Started};
+
+        let lines = |phase, x, y| mouse_wheel(1, phase, LineDelta, x, y);
+        let cases = [
+            // the newer position replaces the older one
+            (
+                cursor_moved(1, 0, 1.0, 2.0),
+                cursor_moved(1, 0, 3.0, 4.0),
+                Some((3.0, 4.0)),
+            ),
+            (
+                cursor_moved(1, 0, 1.0, 2.0),
+                cursor_moved(2, 0, 3.0, 4.0),
+                None,
+            ),
+            (
+                cursor_moved(1, 0, 1.0, 2.0),
+                cursor_moved(1, 1, 3.0, 4.0),
+                None,
+            ),
+            // the scroll deltas accumulate
+            (
+                lines(Moved, 1.0, 2.0),
+                lines(Moved, 3.0, 4.0),
+                Some((4.0, 6.0)),
+            ),
+            (lines(Started, 1.0, 2.0), lines(Moved, 3.0, 4.0), None),
+            (lines(Moved, 1.0, 2.0), lines(Ended, 3.0, 4.0), None),
+            (
+                lines(Moved, 1.0, 2.0),
+                mouse_wheel(1, Moved, PixelDelta, 3.0, 4.0),
+                None,
+            ),
+            (
+                lines(Moved, 1.0, 2.0),
+                mouse_wheel(2, Moved, LineDelta, 3.0, 4.0),
+                None,
+            ),
+            (cursor_moved(1, 0, 1.0, 2.0), lines(Moved, 3.0, 4.0), None),
+            (received_character("a"), received_character("b"), None),
+        ];
+
+        for (mut older, newer, expected) in cases {
+            let event_type = older.event_type;
+            let before = (older.cursor_moved, older.mouse_wheel.delta);
+            assert_eq!(older.coalesce(&newer), expected.is_some(), "{:?}", newer);
+
+            let (cursor, delta) = (older.cursor_moved, older.mouse_wheel.delta);
+            match expected {
+                Some(expected) if event_type == GlutinEventType::WindowEventCursorMoved => {
+                    assert_eq!((cursor.x, cursor.y), expected)
+                }
+                Some(expected) => assert_eq!((delta.x, delta.y), expected),
+                None => {
+                    assert_eq!((cursor.x, cursor.y), (before.0.x, before.0.y));
+                    assert_eq!((delta.x, delta.y), (before.1.x, before.1.y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn touch_force_is_translated_by_kind() {
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        let cases = [
+            (None, GlutinEventTouchForceType::None, 0.0, 0.0, 0.0, None),
+            (
+                Some(Force::Calibrated {
+                    force: 2.0,
+                    max_possible_force: 4.0,
+                    altitude_angle: None,
+                }),
+                GlutinEventTouchForceType::Calibrated,
+                0.5,
+                2.0,
+                4.0,
+                None,
+            ),
+            (
+                Some(Force::Calibrated {
+                    force: 1.0,
+                    max_possible_force: 2.0,
+                    altitude_angle: Some(half_pi),
+                }),
+                GlutinEventTouchForceType::Calibrated,
+                0.5,
+                1.0,
+                2.0,
+                Some(half_pi),
+            ),
+            (
+                Some(Force::Normalized(0.25)),
+                GlutinEventTouchForceType::Normalized,
+                0.25,
+                0.0,
+                0.0,
+                None,
+            ),
+        ];
+
+        for (force, force_type, normalized_force, calibrated_force, max_force, altitude) in cases {
+            let mut c_event = GlutinEvent::default();
+            glutin_event_loop_process_touch(
+                &mut c_event,
+                device_id(),
+                TouchPhase::Moved,
+                PhysicalPosition::new(1.0, 2.0),
+                force,
+                7,
+            );
+            let touch = &c_event.touch;
+            assert_eq!(touch.force_type, force_type);
+            assert_eq!(touch.normalized_force, normalized_force);
+            assert_eq!(touch.force, calibrated_force);
+            assert_eq!(touch.max_possible_force, max_force);
+            assert_eq!(touch.has_altitude_angle, altitude.is_some());
+            assert_eq!(touch.altitude_angle, altitude.unwrap_or_default());
+            assert_eq!((touch.x, touch.y, touch.id), (1.0, 2.0, 7));
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Declarations of the dependency types that appear in the exported functions and structs.
+//! cbindgen does not parse the dependencies of the library, it reads these instead to know
+//! which types are generic and how the structs passed by value are laid out.
+//! The module is only read by cbindgen and never compiled.
+
+/// Opaque, only passed behind pointers
+pub struct ValueBox<T>(T);
+
+/// Opaque, only passed behind pointers
+pub struct StringBox;
+
+/// Opaque, only passed behind pointers
+pub struct PointBox<T>(T);
+
+/// Opaque, only passed behind pointers
+pub struct SizeBox<T>(T);
+
+/// A 128-bit number such as a window id, split in two halves
+#[repr(C)]
+pub struct U128Box {
+    pub low: u64,
+    pub high: u64,
+}
+
+/// `#[repr(u32)]` in winit, its values are the variants in declaration order
+pub type VirtualKeyCode = u32;
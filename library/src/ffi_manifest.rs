@@ -0,0 +1,560 @@
+use std::fmt::Write;
+
+use string_box::StringBox;
+use value_box::ValueBox;
+
+use crate::capabilities::GlutinCapability;
+use crate::enums::{GlutinCursorIcon, GlutinResult, GlutinUserAttentionType, GlutinX11WindowType};
+use crate::event_loop::{GlutinBackendType, GlutinEventLoopType};
+use crate::events::*;
+use crate::logger::GlutinLogLevel;
+use crate::ContextApi;
+
+/// Version of the binary interface of the library. It must be increased whenever the layout
+/// of an exported struct, the value of an exported enum or the calling convention of an
+/// exported function changes, the layout test refuses layout changes that keep the version.
+pub const GLUTIN_ABI_VERSION: u32 = 12;
+
+/// An exported function as declared in the sources
+#[derive(Debug)]
+pub struct FfiFunction {
+    pub name: &'static str,
+    /// The calling convention, `C` for every function of the library
+    pub abi: &'static str,
+    pub parameters: &'static [FfiParameter],
+    pub return_type: &'static str,
+}
+
+#[derive(Debug)]
+pub struct FfiParameter {
+    pub name: &'static str,
+    pub type_name: &'static str,
+}
+
+/// The memory layout of a `#[repr(C)]` struct
+#[derive(Debug)]
+pub struct FfiStruct {
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    pub fields: Vec<FfiField>,
+}
+
+#[derive(Debug)]
+pub struct FfiField {
+    pub name: &'static str,
+    pub type_name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// The size and values of a fieldless enum
+#[derive(Debug)]
+pub struct FfiEnum {
+    pub name: &'static str,
+    pub size: usize,
+    pub variants: Vec<FfiEnumVariant>,
+}
+
+#[derive(Debug)]
+pub struct FfiEnumVariant {
+    pub name: &'static str,
+    pub value: i64,
+}
+
+// the exported functions are collected from the sources by the build script
+include!(concat!(env!("OUT_DIR"), "/ffi_functions.rs"));
+
+/// Describe the layout of a struct given all of its fields in declaration order.
+/// Fails to compile if a field of the struct is not listed.
+#[macro_export]
+macro_rules! ffi_struct {
+    ($name:ident { $($field:ident),* $(,)? }) => {{
+        #[allow(unused)]
+        fn all_fields_are_described(value: &$name) {
+            let $name { $($field: _),* } = value;
+        }
+
+        let value = std::mem::MaybeUninit::<$name>::uninit();
+        let base = value.as_ptr();
+        $crate::ffi_manifest::FfiStruct {
+            name: stringify!($name),
+            size: std::mem::size_of::<$name>(),
+            align: std::mem::align_of::<$name>(),
+            fields: vec![$({
+                let field = unsafe { std::ptr::addr_of!((*base).$field) };
+                $crate::ffi_manifest::FfiField {
+                    name: stringify!($field),
+                    type_name: $crate::ffi_manifest::ffi_type_name(field),
+                    offset: field as usize - base as usize,
+                    size: $crate::ffi_manifest::ffi_type_size(field),
+                }
+            }),*],
+        }
+    }};
+}
+
+/// Describe the values of an enum given all of its variants.
+/// Fails to compile if a variant of the enum is not listed.
+#[macro_export]
+macro_rules! ffi_enum {
+    ($name:ident { $($variant:ident),* $(,)? }) => {{
+        #[allow(unused)]
+        fn all_variants_are_described(value: $name) {
+            match value {
+                $($name::$variant => {}),*
+            }
+        }
+
+        $crate::ffi_manifest::FfiEnum {
+            name: stringify!($name),
+            size: std::mem::size_of::<$name>(),
+            variants: vec![$($crate::ffi_manifest::FfiEnumVariant {
+                name: stringify!($variant),
+                value: $name::$variant as i64,
+            }),*],
+        }
+    }};
+}
+
+/// The name of the type a field points to, without module paths
+pub fn ffi_type_name<T>(_field: *const T) -> String {
+    let mut name = String::new();
+    let mut segment = String::new();
+    let mut characters = std::any::type_name::<T>().chars().peekable();
+    while let Some(character) = characters.next() {
+        if character.is_alphanumeric() || character == '_' {
+            segment.push(character);
+        } else if character == ':' && characters.peek() == Some(&':') {
+            // drop the module of the following segment
+            characters.next();
+            segment.clear();
+        } else {
+            name.push_str(&segment);
+            segment.clear();
+            name.push(character);
+        }
+    }
+    name.push_str(&segment);
+    name
+}
+
+pub fn ffi_type_size<T>(_field: *const T) -> usize {
+    std::mem::size_of::<T>()
+}
+
+/// Everything a host needs to know to bind the library
+#[derive(Debug)]
+pub struct FfiManifest {
+    pub abi_version: u32,
+    pub functions: &'static [FfiFunction],
+    pub structs: Vec<FfiStruct>,
+    pub enums: Vec<FfiEnum>,
+}
+
+impl FfiManifest {
+    pub fn new() -> Self {
+        let mut structs = glutin_event_struct_layouts();
+        structs.push(crate::event_ring_buffer::glutin_event_ring_buffer_layout());
+        structs.sort_by_key(|ffi_struct| ffi_struct.name);
+
+        let mut enums = vec![
+            ffi_enum!(ContextApi {
+                OpenGl,
+                OpenGlEs,
+                WebGl,
+                Unknown
+            }),
+            ffi_enum!(GlutinBackendType { Native, Mock }),
+            ffi_enum!(GlutinCapability {
+                Wayland,
+                Surfaceless,
+                OsMesa,
+                ImePosition,
+                RawDeviceEvents,
+                Fullscreen
+            }),
+            ffi_enum!(GlutinEventLoopType {
+                Windows,
+                MacOS,
+                X11,
+                Wayland,
+                Unknown,
+                Mock
+            }),
+            ffi_enum!(GlutinLogLevel {
+                Off,
+                Error,
+                Warn,
+                Info,
+                Debug,
+                Trace
+            }),
+            ffi_enum!(GlutinResult {
+                Ok,
+                NullPointer,
+                NotSupported,
+                OsError,
+                WrongThread,
+                ContextLost,
+                InvalidArgument
+            }),
+            ffi_enum!(GlutinUserAttentionType {
+                None,
+                Critical,
+                Informational
+            }),
+            ffi_enum!(GlutinX11WindowType {
+                Normal,
+                Desktop,
+                Dock,
+                Toolbar,
+                Menu,
+                Utility,
+                Splash,
+                Dialog,
+                DropdownMenu,
+                PopupMenu,
+                Tooltip,
+                Notification,
+                Combo,
+                Dnd
+            }),
+            glutin_cursor_icon_layout(),
+        ];
+        enums.extend(glutin_event_enum_layouts());
+        enums.sort_by_key(|ffi_enum| ffi_enum.name);
+
+        Self {
+            abi_version: GLUTIN_ABI_VERSION,
+            functions: FFI_FUNCTIONS,
+            structs,
+            enums,
+        }
+    }
+
+    /// The part of the manifest that must not change without a version bump
+    pub fn layout_json(&self) -> String {
+        let mut json = String::new();
+        json.push_str("[\n");
+        // structs are always followed by enums, every struct ends with a separator
+        for ffi_struct in &self.structs {
+            writeln!(
+                json,
+                "    {{ \"struct\": {}, \"size\": {}, \"align\": {}, \"fields\": [",
+                json_string(ffi_struct.name),
+                ffi_struct.size,
+                ffi_struct.align
+            )
+            .unwrap();
+            for (field_index, field) in ffi_struct.fields.iter().enumerate() {
+                writeln!(
+                    json,
+                    "      {{ \"name\": {}, \"type\": {}, \"offset\": {}, \"size\": {} }}{}",
+                    json_string(field.name),
+                    json_string(&field.type_name),
+                    field.offset,
+                    field.size,
+                    json_separator(field_index, ffi_struct.fields.len())
+                )
+                .unwrap();
+            }
+            json.push_str("    ] },\n");
+        }
+        for (index, ffi_enum) in self.enums.iter().enumerate() {
+            writeln!(
+                json,
+                "    {{ \"enum\": {}, \"size\": {}, \"variants\": [",
+                json_string(ffi_enum.name),
+                ffi_enum.size
+            )
+            .unwrap();
+            for (variant_index, variant) in ffi_enum.variants.iter().enumerate() {
+                writeln!(
+                    json,
+                    "      {{ \"name\": {}, \"value\": {} }}{}",
+                    json_string(variant.name),
+                    variant.value,
+                    json_separator(variant_index, ffi_enum.variants.len())
+                )
+                .unwrap();
+            }
+            writeln!(json, "    ] }}{}", json_separator(index, self.enums.len())).unwrap();
+        }
+        json.push_str("  ]");
+        json
+    }
+
+    /// A stable FNV-1a hash of the layout, recorded in the manifest
+    pub fn layout_hash(&self) -> u64 {
+        self.layout_json()
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        json.push_str("{\n");
+        writeln!(json, "  \"abi_version\": {},", self.abi_version).unwrap();
+        writeln!(json, "  \"layout_hash\": \"{:016x}\",", self.layout_hash()).unwrap();
+        json.push_str("  \"functions\": [\n");
+        for (index, function) in self.functions.iter().enumerate() {
+            let parameters = function
+                .parameters
+                .iter()
+                .map(|parameter| {
+                    format!(
+                        "{{ \"name\": {}, \"type\": {} }}",
+                        json_string(parameter.name),
+                        json_string(parameter.type_name)
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            writeln!(
+                json,
+                "    {{ \"name\": {}, \"abi\": {}, \"parameters\": [{}], \"return\": {} }}{}",
+                json_string(function.name),
+                json_string(function.abi),
+                parameters,
+                json_string(function.return_type),
+                json_separator(index, self.functions.len())
+            )
+            .unwrap();
+        }
+        json.push_str("  ],\n");
+        writeln!(json, "  \"layout\": {}", self.layout_json()).unwrap();
+        json.push_str("}\n");
+        json
+    }
+}
+
+fn json_separator(index: usize, length: usize) -> &'static str {
+    if index + 1 < length {
+        ","
+    } else {
+        ""
+    }
+}
+
+fn json_string(string: &str) -> String {
+    let mut json = String::with_capacity(string.len() + 2);
+    json.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            character if (character as u32) < 0x20 => {
+                write!(json, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn glutin_cursor_icon_layout() -> FfiEnum {
+    ffi_enum!(GlutinCursorIcon {
+        Default,
+        Crosshair,
+        Hand,
+        Arrow,
+        Move,
+        Text,
+        Wait,
+        Help,
+        Progress,
+        NotAllowed,
+        ContextMenu,
+        Cell,
+        VerticalText,
+        Alias,
+        Copy,
+        NoDrop,
+        Grab,
+        Grabbing,
+        AllScroll,
+        ZoomIn,
+        ZoomOut,
+        EResize,
+        NResize,
+        NeResize,
+        NwResize,
+        SResize,
+        SeResize,
+        SwResize,
+        WResize,
+        EwResize,
+        NsResize,
+        NeswResize,
+        NwseResize,
+        ColResize,
+        RowResize
+    })
+}
+
+/// Return the FFI manifest of the library as JSON
+#[no_mangle]
+pub extern "C" fn glutin_ffi_manifest() -> *mut ValueBox<StringBox> {
+    ValueBox::new(StringBox::from_string(FfiManifest::new().to_json())).into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/include/Glutin.json");
+
+    fn json_value<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+        let key = format!("\"{}\": ", key);
+        let start = json.find(&key)? + key.len();
+        let end = start + json[start..].find(',')?;
+        Some(json[start..end].trim_matches('"'))
+    }
+
+    /// The manifest is committed so that bindings can be generated without building the library.
+    /// A changed layout is only accepted together with a new `GLUTIN_ABI_VERSION`. The test never
+    /// writes the manifest unless `GLUTIN_UPDATE_MANIFEST` is set, which regenerates it.
+    #[test]
+    fn layout_changes_require_a_version_bump() {
+        let manifest = FfiManifest::new();
+        let json = manifest.to_json();
+        let hash = format!("{:016x}", manifest.layout_hash());
+        let version = manifest.abi_version.to_string();
+
+        let recorded = std::fs::read_to_string(MANIFEST_PATH).unwrap_or_default();
+        let recorded_version = json_value(&recorded, "abi_version");
+        let recorded_hash = json_value(&recorded, "layout_hash");
+        assert!(
+            recorded_hash == Some(hash.as_str()) || recorded_version != Some(version.as_str()),
+            "The FFI layout changed, increase GLUTIN_ABI_VERSION from {}",
+            version
+        );
+
+        if std::env::var_os("GLUTIN_UPDATE_MANIFEST").is_some() {
+            std::fs::write(MANIFEST_PATH, json).unwrap();
+            return;
+        }
+
+        assert!(
+            recorded_version == Some(version.as_str()) && recorded_hash == Some(hash.as_str()),
+            "{} is outdated, regenerate it with GLUTIN_UPDATE_MANIFEST=1 cargo test",
+            MANIFEST_PATH
+        );
+        // the exported functions differ between platforms, the manifest describes Linux
+        if cfg!(target_os = "linux") {
+            assert!(
+                recorded == json,
+                "The exported functions changed, regenerate {} with \
+                 GLUTIN_UPDATE_MANIFEST=1 cargo test",
+                MANIFEST_PATH
+            );
+        }
+    }
+
+    /// Types that can be passed by value without being described in the manifest
+    const FFI_SAFE_TYPES: &[&str] = &[
+        "()",
+        "bool",
+        "u8",
+        "u16",
+        "u32",
+        "u64",
+        "i32",
+        "f64",
+        "usize",
+        "GlutinLogCallback",
+    ];
+
+    #[test]
+    fn exported_functions_use_the_c_calling_convention() {
+        assert!(!FFI_FUNCTIONS.is_empty());
+
+        let functions: Vec<&str> = FFI_FUNCTIONS
+            .iter()
+            .filter(|function| function.abi != "C")
+            .map(|function| function.name)
+            .collect();
+        assert!(functions.is_empty(), "Not extern \"C\": {:?}", functions);
+    }
+
+    #[test]
+    fn exported_functions_pass_described_types() {
+        let manifest = FfiManifest::new();
+        let is_ffi_safe = |type_name: &str| {
+            type_name.starts_with('*')
+                || type_name.starts_with("extern \"C\" fn")
+                || FFI_SAFE_TYPES.contains(&type_name)
+                || manifest
+                    .structs
+                    .iter()
+                    .any(|ffi_struct| ffi_struct.name == type_name)
+                || manifest
+                    .enums
+                    .iter()
+                    .any(|ffi_enum| ffi_enum.name == type_name)
+        };
+
+        let mut undescribed = vec![];
+        for function in FFI_FUNCTIONS {
+            for parameter in function.parameters {
+                if !is_ffi_safe(parameter.type_name) {
+                    undescribed.push(format!("{}: {}", function.name, parameter.type_name));
+                }
+            }
+            if !is_ffi_safe(function.return_type) {
+                undescribed.push(format!("{}: {}", function.name, function.return_type));
+            }
+        }
+        assert!(
+            undescribed.is_empty(),
+            "Undescribed types: {:?}",
+            undescribed
+        );
+    }
+
+    /// Fails to compile if one of the functions loses `extern "C"` or changes its signature
+    #[test]
+    fn exported_functions_coerce_to_c_function_pointers() {
+        use crate::context_builder::*;
+        use crate::event_loop::*;
+        use crate::windowed_context::*;
+        use glutin::monitor::MonitorHandle;
+
+        let _: extern "C" fn() -> *mut ValueBox<GlutinEventLoop> = glutin_create_events_loop;
+        let _: extern "C" fn(*mut ValueBox<GlutinEventLoop>) -> GlutinResult =
+            glutin_destroy_events_loop;
+        let _: extern "C" fn(*mut ValueBox<GlutinEventLoop>) -> GlutinEventLoopType =
+            glutin_events_loop_get_type;
+        let _: extern "C" fn(
+            *mut ValueBox<GlutinEventLoop>,
+        ) -> *mut ValueBox<GlutinEventLoopProxy> = glutin_events_loop_create_proxy;
+        let _: extern "C" fn(*mut ValueBox<GlutinEventLoopProxy>) = glutin_events_loop_drop_proxy;
+        let _: extern "C" fn(*mut ValueBox<GlutinEventLoop>) -> *mut ValueBox<MonitorHandle> =
+            glutin_events_loop_get_primary_monitor;
+        let _: extern "C" fn(*mut ValueBox<MonitorHandle>) = glutin_primary_monitor_free;
+        let _: extern "C" fn(*mut ValueBox<MonitorHandle>) -> f64 =
+            glutin_primary_monitor_get_hidpi_factor;
+        let _: extern "C" fn() -> *mut ValueBox<GlutinContextBuilder> =
+            glutin_context_builder_default;
+        let _: extern "C" fn(
+            *mut ValueBox<GlutinEventLoop>,
+            *mut ValueBox<glutin::window::WindowBuilder>,
+            *mut ValueBox<GlutinContextBuilder>,
+        ) -> *mut ValueBox<GlutinWindowedContext> = glutin_create_windowed_context;
+        let _: extern "C" fn(*mut ValueBox<GlutinWindowedContext>) -> GlutinResult =
+            glutin_windowed_context_swap_buffers;
+        let _: extern "C" fn(*mut GlutinEvent) = glutin_event_drop;
+        let _: extern "C" fn() -> *mut ValueBox<StringBox> = glutin_ffi_manifest;
+    }
+
+    #[test]
+    fn type_names_are_without_modules() {
+        let field: *const *mut ValueBox<StringBox> = std::ptr::null();
+        assert_eq!(ffi_type_name(field), "*mut ValueBox<StringBox>");
+    }
+}
@@ -1,7 +1,10 @@
 use crate::context_builder::GlutinContextBuilder;
-use crate::event_loop::GlutinEventLoop;
+use crate::enums::GlutinResult;
+use crate::event_loop::{GlutinEventLoop, GlutinLoopEvent};
+use crate::thread_guard::GlutinThreadGuardedPointer;
 use crate::ContextApi;
 use glutin::dpi::PhysicalSize;
+use glutin::event_loop::EventLoop;
 use glutin::{
     Api, Context, ContextBuilder, ContextCurrentState, ContextError, CreationError, NotCurrent,
     PossiblyCurrent,
@@ -92,7 +95,7 @@ impl GlutinHeadlessContext {
 #[cfg(target_os = "linux")]
 fn build_context_surfaceless<T1: ContextCurrentState>(
     cb: ContextBuilder<T1>,
-    el: &GlutinEventLoop,
+    el: &EventLoop<GlutinLoopEvent>,
 ) -> Result<Context<NotCurrent>, CreationError> {
     use glutin::platform::unix::EventLoopWindowTargetExtUnix;
     use glutin::platform::unix::HeadlessContextExt;
@@ -104,9 +107,16 @@ fn build_context_surfaceless<T1: ContextCurrentState>(
     cb.build_surfaceless(el)
 }
 
+/// Return true if a surfaceless context can be built with the event loop, which is checked
+/// by building one the same way as `glutin_create_headless_context` does
+#[cfg(target_os = "linux")]
+pub(crate) fn glutin_is_surfaceless_available(el: &EventLoop<GlutinLoopEvent>) -> bool {
+    build_context_surfaceless(ContextBuilder::new(), el).is_ok()
+}
+
 fn build_context_headless<T1: ContextCurrentState>(
     cb: ContextBuilder<T1>,
-    el: &GlutinEventLoop,
+    el: &EventLoop<GlutinLoopEvent>,
 ) -> Result<Context<NotCurrent>, CreationError> {
     let size_one = PhysicalSize::new(1, 1);
     cb.build_headless(el, size_one)
@@ -123,7 +133,7 @@ fn build_context_osmesa<T1: ContextCurrentState>(
 
 #[cfg(target_os = "linux")]
 fn build_context<T1: ContextCurrentState>(
-    el: &GlutinEventLoop,
+    el: &EventLoop<GlutinLoopEvent>,
     cb: ContextBuilder<T1>,
 ) -> Result<Context<NotCurrent>, [CreationError; 3]> {
     // On unix operating systems, you should always try for surfaceless first,
@@ -156,7 +166,7 @@ fn build_context<T1: ContextCurrentState>(
 
 #[cfg(not(target_os = "linux"))]
 fn build_context<T1: ContextCurrentState>(
-    el: &GlutinEventLoop,
+    el: &EventLoop<GlutinLoopEvent>,
     cb: ContextBuilder<T1>,
 ) -> Result<Context<NotCurrent>, CreationError> {
     debug!("Trying headless with {:?}", &cb);
@@ -164,15 +174,24 @@ fn build_context<T1: ContextCurrentState>(
 }
 
 #[no_mangle]
-pub fn glutin_create_headless_context(
+pub extern "C" fn glutin_create_headless_context(
     _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
     mut _ptr_context_builder: *mut ValueBox<GlutinContextBuilder>,
 ) -> *mut ValueBox<GlutinHeadlessContext> {
+    if _ptr_events_loop.check_owner_thread() != GlutinResult::Ok {
+        return std::ptr::null_mut();
+    }
+
     _ptr_events_loop
         .with_mut(|event_loop| {
             _ptr_context_builder
                 .take_value()
                 .and_then(|context_builder| {
+                    let event_loop = event_loop.native().ok_or_else(|| {
+                        BoxerError::AnyError(
+                            "The mock backend does not support headless contexts".into(),
+                        )
+                    })?;
                     let context = match context_builder {
                         GlutinContextBuilder::NotCurrent(builder) => {
                             build_context(event_loop, builder.clone())
@@ -191,7 +210,7 @@ pub fn glutin_create_headless_context(
 
 // I *do not* consume the context builder
 #[no_mangle]
-pub fn glutin_try_headless_context(
+pub extern "C" fn glutin_try_headless_context(
     _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
     mut _ptr_context_builder: *mut ValueBox<GlutinContextBuilder>,
 ) -> bool {
@@ -204,22 +223,26 @@ pub fn glutin_try_headless_context(
 }
 
 #[no_mangle]
-pub fn glutin_context_make_current(mut _ptr: *mut ValueBox<GlutinHeadlessContext>) {
+pub extern "C" fn glutin_context_make_current(mut _ptr: *mut ValueBox<GlutinHeadlessContext>) {
     _ptr.replace_value(|context| context.make_current()).log();
 }
 
 #[no_mangle]
-pub fn glutin_context_is_current(_ptr_context: *mut ValueBox<GlutinHeadlessContext>) -> bool {
+pub extern "C" fn glutin_context_is_current(
+    _ptr_context: *mut ValueBox<GlutinHeadlessContext>,
+) -> bool {
     _ptr_context.with_not_null_return(false, |context| context.is_current())
 }
 
 #[no_mangle]
-pub fn glutin_context_get_api(_ptr_context: *mut ValueBox<GlutinHeadlessContext>) -> ContextApi {
+pub extern "C" fn glutin_context_get_api(
+    _ptr_context: *mut ValueBox<GlutinHeadlessContext>,
+) -> ContextApi {
     _ptr_context.with_not_null_return(ContextApi::Unknown, |context| context.get_api().into())
 }
 
 #[no_mangle]
-pub fn glutin_context_get_proc_address(
+pub extern "C" fn glutin_context_get_proc_address(
     _ptr_context: *mut ValueBox<GlutinHeadlessContext>,
     _ptr_symbol: *mut ValueBox<StringBox>,
 ) -> *const c_void {
@@ -231,6 +254,6 @@ pub fn glutin_context_get_proc_address(
 }
 
 #[no_mangle]
-pub fn glutin_destroy_context(_ptr: *mut ValueBox<GlutinHeadlessContext>) {
+pub extern "C" fn glutin_destroy_context(_ptr: *mut ValueBox<GlutinHeadlessContext>) {
     _ptr.release();
 }
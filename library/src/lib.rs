@@ -3,13 +3,26 @@
 #[macro_use]
 extern crate log;
 
+pub mod capabilities;
+pub mod command_queue;
 pub mod context_builder;
+pub mod cursor;
 pub mod enums;
+pub mod event_filter;
+pub mod event_injection;
 pub mod event_loop;
+pub mod event_recording;
+pub mod event_ring_buffer;
 pub mod events;
+#[cfg(cbindgen)]
+mod ffi_header;
+pub mod ffi_manifest;
 pub mod headless_context;
+pub mod logger;
+pub mod mock;
 pub mod pixel_format;
 pub mod pixel_format_requirements;
+pub mod thread_guard;
 pub mod window_builder;
 pub mod windowed_context;
 
@@ -17,13 +30,19 @@ pub mod windowed_context;
 #[path = "platform/macos.rs"]
 mod ext;
 
-#[cfg(all(not(target_os = "macos")))]
+#[cfg(target_os = "linux")]
+#[path = "platform/linux.rs"]
+mod ext;
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "linux")))]
 #[path = "platform/others.rs"]
 mod ext;
 
 use geometry_box::U128Box;
+use glutin::event::DeviceId;
 use glutin::window::WindowId;
 use glutin::Api;
+use std::hash::{Hash, Hasher};
 use std::mem::transmute_copy;
 use string_box::StringBox;
 use value_box::{ValueBox, ValueBoxPointer};
@@ -59,22 +78,40 @@ impl From<Api> for ContextApi {
 }
 
 #[no_mangle]
-pub fn glutin_test() -> bool {
+pub extern "C" fn glutin_test() -> bool {
     return true;
 }
 
+/// Return the version of the library, for example `1.0.0`
+#[no_mangle]
+pub extern "C" fn glutin_library_version() -> *mut ValueBox<StringBox> {
+    ValueBox::new(StringBox::from_string(String::from(env!(
+        "CARGO_PKG_VERSION"
+    ))))
+    .into_raw()
+}
+
+/// Return the version of the binary interface. It changes whenever the layout of `GlutinEvent`
+/// or of another exported struct or enum changes, so that the host can refuse a library
+/// it was not written for instead of reading events with a wrong layout.
 #[no_mangle]
-pub fn glutin_init_logger() {
-    env_logger::init();
+pub extern "C" fn glutin_abi_version() -> u32 {
+    ffi_manifest::GLUTIN_ABI_VERSION
 }
 
+/// Return the size of `GlutinEvent` in bytes
 #[no_mangle]
-pub fn glutin_println(_ptr_message: *mut ValueBox<StringBox>) {
+pub extern "C" fn glutin_abi_event_size() -> usize {
+    std::mem::size_of::<events::GlutinEvent>()
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_println(_ptr_message: *mut ValueBox<StringBox>) {
     _ptr_message.with_not_null(|message| println!("{}", message.to_string()));
 }
 
 #[no_mangle]
-pub fn glutin_print(_ptr_message: *mut ValueBox<StringBox>) {
+pub extern "C" fn glutin_print(_ptr_message: *mut ValueBox<StringBox>) {
     _ptr_message.with_not_null(|message| print!("{}", message.to_string()));
 }
 
@@ -105,3 +142,96 @@ pub fn glutin_convert_window_id(window_id: WindowId) -> U128Box {
 
     id_128.into()
 }
+
+/// Convert a device id to the platform integer it wraps. winit only exposes that integer
+/// through `Hash`, so the device id is hashed with a hasher that keeps the written integers.
+/// Devices of the same event loop keep their id for as long as they are connected.
+pub fn glutin_convert_device_id(device_id: DeviceId) -> i64 {
+    let mut hasher = DeviceIdHasher(0);
+    device_id.hash(&mut hasher);
+    hasher.finish() as i64
+}
+
+/// Appends every written integer to the lower bits, dropping the bits that do not fit
+struct DeviceIdHasher(u64);
+
+impl DeviceIdHasher {
+    fn append(&mut self, value: u64, bits: u32) {
+        self.0 = self.0.checked_shl(bits).unwrap_or(0) | value;
+    }
+}
+
+impl Hasher for DeviceIdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.append(*byte as u64, 8);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.append(value as u64, 8);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.append(value as u64, 16);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.append(value as u64, 32);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.append(value, 64);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.append(value as u64, usize::BITS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Write {
+        Bytes(&'static [u8]),
+        U8(u8),
+        U16(u16),
+        U32(u32),
+        U64(u64),
+        Usize(usize),
+    }
+
+    #[test]
+    fn device_id_hasher_keeps_the_written_integers() {
+        let cases: [(&[Write], u64); 7] = [
+            (&[], 0),
+            (&[Write::U8(0x12)], 0x12),
+            (&[Write::Bytes(&[0x01, 0x02])], 0x0102),
+            (&[Write::U16(0x1234), Write::U8(0x56)], 0x123456),
+            (&[Write::U32(7), Write::U32(9)], 7 << 32 | 9),
+            // a 64 bit integer leaves no room for the integers written before it
+            (&[Write::U64(1), Write::U64(5)], 5),
+            (&[Write::Usize(3)], 3),
+        ];
+
+        for (writes, expected) in cases {
+            let mut hasher = DeviceIdHasher(0);
+            for write in writes {
+                match write {
+                    Write::Bytes(bytes) => hasher.write(bytes),
+                    Write::U8(value) => hasher.write_u8(*value),
+                    Write::U16(value) => hasher.write_u16(*value),
+                    Write::U32(value) => hasher.write_u32(*value),
+                    Write::U64(value) => hasher.write_u64(*value),
+                    Write::Usize(value) => hasher.write_usize(*value),
+                }
+            }
+            assert_eq!(hasher.finish(), expected);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+
+use env_logger::filter::{Builder, Filter};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use string_box::StringBox;
+use value_box::{ValueBox, ValueBoxPointer};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GlutinLogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Default for GlutinLogLevel {
+    fn default() -> Self {
+        GlutinLogLevel::Error
+    }
+}
+
+impl From<GlutinLogLevel> for LevelFilter {
+    fn from(level: GlutinLogLevel) -> Self {
+        match level {
+            GlutinLogLevel::Off => LevelFilter::Off,
+            GlutinLogLevel::Error => LevelFilter::Error,
+            GlutinLogLevel::Warn => LevelFilter::Warn,
+            GlutinLogLevel::Info => LevelFilter::Info,
+            GlutinLogLevel::Debug => LevelFilter::Debug,
+            GlutinLogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+impl From<Level> for GlutinLogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => GlutinLogLevel::Error,
+            Level::Warn => GlutinLogLevel::Warn,
+            Level::Info => GlutinLogLevel::Info,
+            Level::Debug => GlutinLogLevel::Debug,
+            Level::Trace => GlutinLogLevel::Trace,
+        }
+    }
+}
+
+/// Receives the level, the target and the message of a log record. The strings are owned
+/// by the library and are only valid during the call.
+///
+/// The callback is called on the thread that logs the record. This is usually the thread
+/// running the event loop, but the library also logs from other threads, for example when
+/// a window is used from a thread that does not own it or when a window command is queued
+/// from another thread. Several calls may run at the same time, so the callback must be
+/// thread-safe and must not assume it runs on the thread of the event loop.
+pub type GlutinLogCallback =
+    extern "C" fn(GlutinLogLevel, *mut ValueBox<StringBox>, *mut ValueBox<StringBox>);
+
+enum LoggerOutput {
+    Stderr,
+    File(File),
+    Callback(GlutinLogCallback),
+}
+
+struct LoggerConfiguration {
+    /// The level of targets that are not mentioned by the filters
+    level: Option<LevelFilter>,
+    /// Filters in the syntax of `RUST_LOG`, for example `Glutin=debug,winit=warn`
+    filters: String,
+    filter: Filter,
+    output: LoggerOutput,
+}
+
+impl LoggerConfiguration {
+    fn new(level: Option<LevelFilter>, filters: String, output: LoggerOutput) -> Self {
+        let mut builder = Builder::new();
+        if let Some(level) = level {
+            builder.filter_level(level);
+        }
+        builder.parse(&filters);
+
+        Self {
+            level,
+            filters,
+            filter: builder.build(),
+            output,
+        }
+    }
+}
+
+/// A logger that is installed once and can be reconfigured any time after that.
+/// Until it is configured nothing is logged.
+struct GlutinLogger {
+    configuration: Mutex<Option<LoggerConfiguration>>,
+}
+
+static LOGGER: GlutinLogger = GlutinLogger {
+    configuration: Mutex::new(None),
+};
+
+impl Log for GlutinLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        match self.configuration.lock() {
+            Ok(configuration) => configuration.as_ref().map_or(false, |configuration| {
+                configuration.filter.enabled(metadata)
+            }),
+            Err(_) => false,
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        let mut configuration = match self.configuration.lock() {
+            Ok(configuration) => configuration,
+            Err(_) => return,
+        };
+        let callback = match configuration.as_mut() {
+            None => return,
+            Some(configuration) => {
+                if !configuration.filter.matches(record) {
+                    return;
+                }
+                match &mut configuration.output {
+                    LoggerOutput::Stderr => {
+                        eprintln!("{}", format_record(record));
+                        return;
+                    }
+                    LoggerOutput::File(file) => {
+                        // there is nowhere to report a failed write to the log
+                        writeln!(file, "{}", format_record(record)).ok();
+                        return;
+                    }
+                    LoggerOutput::Callback(callback) => *callback,
+                }
+            }
+        };
+        // the host may log from the callback, so it is called without holding the lock
+        drop(configuration);
+
+        let target = ValueBox::new(StringBox::from_string(record.target().to_string())).into_raw();
+        let message = ValueBox::new(StringBox::from_string(record.args().to_string())).into_raw();
+        callback(record.level().into(), target, message);
+        target.release();
+        message.release();
+    }
+
+    fn flush(&self) {
+        if let Ok(mut configuration) = self.configuration.lock() {
+            if let Some(LoggerConfiguration {
+                output: LoggerOutput::File(file),
+                ..
+            }) = configuration.as_mut()
+            {
+                file.flush().ok();
+            }
+        }
+    }
+}
+
+fn format_record(record: &Record) -> String {
+    format!(
+        "[{:<5} {}] {}",
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+/// Install the logger if it is not installed yet and replace its configuration
+fn configure(update: impl FnOnce(Option<LoggerConfiguration>) -> LoggerConfiguration) {
+    // fails if the logger is already installed, which is fine
+    log::set_logger(&LOGGER).ok();
+
+    let mut configuration = match LOGGER.configuration.lock() {
+        Ok(configuration) => configuration,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let new_configuration = update(configuration.take());
+    log::set_max_level(new_configuration.filter.filter());
+    *configuration = Some(new_configuration);
+}
+
+/// Log to stderr with the filters of the `RUST_LOG` environment variable, or only errors
+/// if it is not set. Unlike `env_logger::init` it can be called more than once,
+/// every call resets the level, the filters and the output.
+#[no_mangle]
+pub extern "C" fn glutin_init_logger() {
+    let filters = std::env::var("RUST_LOG").unwrap_or_default();
+    let level = if filters.is_empty() {
+        Some(LevelFilter::Error)
+    } else {
+        None
+    };
+    configure(|_| LoggerConfiguration::new(level, filters, LoggerOutput::Stderr));
+}
+
+/// Set the level of all targets that are not mentioned by the filters
+#[no_mangle]
+pub extern "C" fn glutin_logger_set_level(level: GlutinLogLevel) {
+    configure(|configuration| match configuration {
+        None => LoggerConfiguration::new(Some(level.into()), String::new(), LoggerOutput::Stderr),
+        Some(configuration) => LoggerConfiguration::new(
+            Some(level.into()),
+            configuration.filters,
+            configuration.output,
+        ),
+    });
+}
+
+/// Set the filters in the syntax of `RUST_LOG`, for example `Glutin=debug,winit=warn`.
+/// An empty string removes all filters.
+#[no_mangle]
+pub extern "C" fn glutin_logger_set_filters(_ptr_filters: *mut ValueBox<StringBox>) {
+    _ptr_filters.with_not_null(|filters| {
+        let filters = filters.to_string();
+        configure(|configuration| match configuration {
+            None => LoggerConfiguration::new(None, filters, LoggerOutput::Stderr),
+            Some(configuration) => {
+                LoggerConfiguration::new(configuration.level, filters, configuration.output)
+            }
+        });
+    });
+}
+
+fn set_output(output: LoggerOutput) {
+    configure(|configuration| match configuration {
+        None => LoggerConfiguration::new(Some(LevelFilter::Error), String::new(), output),
+        Some(configuration) => {
+            LoggerConfiguration::new(configuration.level, configuration.filters, output)
+        }
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_logger_log_to_stderr() {
+    set_output(LoggerOutput::Stderr);
+}
+
+/// Append log records to a file, creating it if needed.
+/// Returns false if the file could not be opened, the output is then left unchanged.
+#[no_mangle]
+pub extern "C" fn glutin_logger_log_to_file(_ptr_path: *mut ValueBox<StringBox>) -> bool {
+    _ptr_path.with_not_null_return(false, |path| {
+        let path = path.to_string();
+        match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => {
+                set_output(LoggerOutput::File(file));
+                true
+            }
+            Err(error) => {
+                error!(
+                    "[glutin_logger_log_to_file] Could not open {}: {}",
+                    path, error
+                );
+                false
+            }
+        }
+    })
+}
+
+/// Send log records to the host. The callback may be called from any thread,
+/// see `GlutinLogCallback`.
+#[no_mangle]
+pub extern "C" fn glutin_logger_log_to_callback(callback: GlutinLogCallback) {
+    set_output(LoggerOutput::Callback(callback));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static RECORDS: Mutex<Vec<(GlutinLogLevel, String, String)>> = Mutex::new(Vec::new());
+
+    extern "C" fn record(
+        level: GlutinLogLevel,
+        target: *mut ValueBox<StringBox>,
+        message: *mut ValueBox<StringBox>,
+    ) {
+        let target = target.with_not_null_return(String::new(), |target| target.to_string());
+        let message = message.with_not_null_return(String::new(), |message| message.to_string());
+        if target.starts_with("logger_test") {
+            RECORDS.lock().unwrap().push((level, target, message));
+        }
+    }
+
+    #[test]
+    fn records_are_filtered_and_sent_to_the_callback() {
+        glutin_init_logger();
+        glutin_init_logger();
+        glutin_logger_log_to_callback(record);
+        glutin_logger_set_level(GlutinLogLevel::Off);
+        let filters = ValueBox::new(StringBox::from_string(String::from(
+            "logger_test::quiet=error,logger_test=info",
+        )))
+        .into_raw();
+        glutin_logger_set_filters(filters);
+        filters.release();
+
+        info!(target: "logger_test", "shown");
+        debug!(target: "logger_test", "hidden by level");
+        warn!(target: "logger_test::quiet", "hidden by target");
+        error!(target: "logger_test::quiet", "shown too");
+
+        assert_eq!(
+            *RECORDS.lock().unwrap(),
+            vec![
+                (
+                    GlutinLogLevel::Info,
+                    String::from("logger_test"),
+                    String::from("shown")
+                ),
+                (
+                    GlutinLogLevel::Error,
+                    String::from("logger_test::quiet"),
+                    String::from("shown too")
+                ),
+            ]
+        );
+    }
+}
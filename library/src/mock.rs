@@ -0,0 +1,602 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use geometry_box::U128Box;
+use glutin::dpi::{PhysicalPosition, PhysicalSize};
+use glutin::error::{ExternalError, NotSupportedError};
+use glutin::event::{Event, StartCause};
+use glutin::event_loop::ControlFlow;
+use glutin::window::{CursorIcon, UserAttentionType, Window, WindowBuilder, WindowId};
+use string_box::StringBox;
+use value_box::ValueBox;
+
+use crate::event_loop::GlutinLoopEvent;
+use crate::thread_guard::GlutinThreadGuardedPointer;
+use crate::windowed_context::{GlutinWindow, GlutinWindowedContext};
+
+/// An event loop of the mock backend. It has no connection to a display server,
+/// the only events it delivers are the loop's own lifecycle events, redraw requests
+/// of its windows and events injected by the host.
+#[derive(Debug, Default)]
+pub struct MockEventLoop {
+    next_window_id: u64,
+    /// ids of windows that requested a redraw, shared with the windows
+    redraw_requests: Rc<RefCell<Vec<U128Box>>>,
+}
+
+impl MockEventLoop {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn create_window(&mut self, window_builder: WindowBuilder) -> MockWindow {
+        self.next_window_id += 1;
+        let mut id = U128Box::default();
+        id.low = self.next_window_id;
+
+        MockWindow::new(id, window_builder, self.redraw_requests.clone())
+    }
+
+    /// Run the loop like winit's `run_return`. Events of the mock windows carry
+    /// their window id separately, because winit window ids can not be created for them.
+    /// Unlike winit, the loop stops as soon as the handler asks to exit.
+    /// A loop that is asked to wait without a timeout while nothing is pending
+    /// would never wake up, so it exits instead.
+    pub fn run_return<F>(&mut self, mut event_handler: F)
+    where
+        F: FnMut(Event<'_, GlutinLoopEvent>, Option<U128Box>, &mut ControlFlow),
+    {
+        let mut control_flow = ControlFlow::Poll;
+        let mut start_cause = StartCause::Init;
+
+        // returns true once the handler asked to exit
+        let mut emit = |event: Event<'static, GlutinLoopEvent>,
+                        window_id: Option<U128Box>,
+                        control_flow: &mut ControlFlow| {
+            event_handler(event, window_id, control_flow);
+            *control_flow == ControlFlow::Exit
+        };
+
+        'running: loop {
+            if emit(Event::NewEvents(start_cause), None, &mut control_flow)
+                || emit(Event::MainEventsCleared, None, &mut control_flow)
+            {
+                break;
+            }
+
+            let redraw_requests = std::mem::take(&mut *self.redraw_requests.borrow_mut());
+            for window_id in redraw_requests {
+                let event = Event::RedrawRequested(unsafe { WindowId::dummy() });
+                if emit(event, Some(window_id), &mut control_flow) {
+                    break 'running;
+                }
+            }
+            if emit(Event::RedrawEventsCleared, None, &mut control_flow) {
+                break;
+            }
+
+            let has_redraw_requests = !self.redraw_requests.borrow().is_empty();
+            let start = Instant::now();
+            start_cause = match control_flow {
+                ControlFlow::Exit => break,
+                ControlFlow::Poll => StartCause::Poll,
+                ControlFlow::Wait => {
+                    if !has_redraw_requests {
+                        warn!("The mock event loop has no events to wait for, exiting");
+                        break;
+                    }
+                    StartCause::WaitCancelled {
+                        start,
+                        requested_resume: None,
+                    }
+                }
+                ControlFlow::WaitUntil(requested_resume) => {
+                    if has_redraw_requests {
+                        StartCause::WaitCancelled {
+                            start,
+                            requested_resume: Some(requested_resume),
+                        }
+                    } else {
+                        if requested_resume > start {
+                            std::thread::sleep(requested_resume - start);
+                        }
+                        StartCause::ResumeTimeReached {
+                            start,
+                            requested_resume,
+                        }
+                    }
+                }
+            };
+        }
+
+        control_flow = ControlFlow::Exit;
+        emit(Event::LoopDestroyed, None, &mut control_flow);
+    }
+}
+
+/// The state of a mock window, changed by the window accessors
+#[derive(Debug, Clone)]
+pub struct MockWindowState {
+    pub title: String,
+    pub inner_size: PhysicalSize<u32>,
+    pub position: PhysicalPosition<i32>,
+    pub cursor_icon: CursorIcon,
+    pub cursor_visible: bool,
+    pub cursor_grabbed: bool,
+    pub cursor_position: PhysicalPosition<f64>,
+    pub ime_position: PhysicalPosition<i32>,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    pub minimized: bool,
+    pub visible: bool,
+    pub focused: bool,
+    pub decorations: bool,
+    pub resizable: bool,
+    pub always_on_top: bool,
+}
+
+/// An in-memory window of the mock backend
+#[derive(Debug)]
+pub struct MockWindow {
+    id: U128Box,
+    state: RefCell<MockWindowState>,
+    redraw_requests: Rc<RefCell<Vec<U128Box>>>,
+}
+
+impl MockWindow {
+    fn new(
+        id: U128Box,
+        window_builder: WindowBuilder,
+        redraw_requests: Rc<RefCell<Vec<U128Box>>>,
+    ) -> Self {
+        let attributes = window_builder.window;
+        let state = MockWindowState {
+            title: attributes.title,
+            inner_size: attributes
+                .inner_size
+                .map(|size| size.to_physical(1.0))
+                .unwrap_or_else(|| PhysicalSize::new(800, 600)),
+            position: attributes
+                .position
+                .map(|position| position.to_physical(1.0))
+                .unwrap_or_else(|| PhysicalPosition::new(0, 0)),
+            cursor_icon: CursorIcon::Default,
+            cursor_visible: true,
+            cursor_grabbed: false,
+            cursor_position: PhysicalPosition::new(0.0, 0.0),
+            ime_position: PhysicalPosition::new(0, 0),
+            maximized: attributes.maximized,
+            fullscreen: attributes.fullscreen.is_some(),
+            minimized: false,
+            visible: attributes.visible,
+            focused: false,
+            decorations: attributes.decorations,
+            resizable: attributes.resizable,
+            always_on_top: attributes.always_on_top,
+        };
+
+        Self {
+            id,
+            state: RefCell::new(state),
+            redraw_requests,
+        }
+    }
+
+    pub fn state(&self) -> MockWindowState {
+        self.state.borrow().clone()
+    }
+}
+
+impl GlutinWindow for MockWindow {
+    fn native_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn window_id(&self) -> U128Box {
+        self.id.clone()
+    }
+
+    fn request_redraw(&self) {
+        let mut redraw_requests = self.redraw_requests.borrow_mut();
+        let is_requested = redraw_requests
+            .iter()
+            .any(|id| id.low == self.id.low && id.high == self.id.high);
+        if !is_requested {
+            redraw_requests.push(self.id.clone());
+        }
+    }
+
+    fn scale_factor(&self) -> f64 {
+        1.0
+    }
+
+    fn inner_size(&self) -> PhysicalSize<u32> {
+        self.state.borrow().inner_size
+    }
+
+    fn set_inner_size(&self, size: PhysicalSize<u32>) {
+        self.state.borrow_mut().inner_size = size;
+    }
+
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        Ok(self.state.borrow().position)
+    }
+
+    fn set_outer_position(&self, position: PhysicalPosition<i32>) {
+        self.state.borrow_mut().position = position;
+    }
+
+    fn set_title(&self, title: &str) {
+        self.state.borrow_mut().title = title.to_string();
+    }
+
+    fn set_cursor_icon(&self, cursor: CursorIcon) {
+        self.state.borrow_mut().cursor_icon = cursor;
+    }
+
+    fn set_cursor_visible(&self, visible: bool) {
+        self.state.borrow_mut().cursor_visible = visible;
+    }
+
+    fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
+        self.state.borrow_mut().cursor_grabbed = grab;
+        Ok(())
+    }
+
+    fn set_cursor_position(&self, position: PhysicalPosition<f64>) -> Result<(), ExternalError> {
+        self.state.borrow_mut().cursor_position = position;
+        Ok(())
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        self.state.borrow_mut().maximized = maximized;
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.state.borrow().maximized
+    }
+
+    fn set_fullscreen(&self, fullscreen: bool) {
+        self.state.borrow_mut().fullscreen = fullscreen;
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.state.borrow().fullscreen
+    }
+
+    fn set_minimized(&self, minimized: bool) {
+        self.state.borrow_mut().minimized = minimized;
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.state.borrow_mut().visible = visible;
+    }
+
+    fn set_ime_position(&self, position: PhysicalPosition<i32>) {
+        self.state.borrow_mut().ime_position = position;
+    }
+
+    fn focus_window(&self) {
+        self.state.borrow_mut().focused = true;
+    }
+
+    fn request_user_attention(&self, _request_type: Option<UserAttentionType>) {}
+
+    /// There is no pointer to drag mock windows with, dragging does nothing
+    fn drag_window(&self) -> Result<(), ExternalError> {
+        Ok(())
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.state.borrow_mut().decorations = decorations;
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.state.borrow_mut().resizable = resizable;
+    }
+
+    fn set_always_on_top(&self, always_on_top: bool) {
+        self.state.borrow_mut().always_on_top = always_on_top;
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_is_mock(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| match window {
+        GlutinWindowedContext::Mock(_) => true,
+        _ => false,
+    })
+}
+
+/// Return the title of a mock window, or null if the window is not a mock window.
+/// Native windows do not allow reading their title back.
+#[no_mangle]
+pub extern "C" fn glutin_mock_window_get_title(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> *mut ValueBox<StringBox> {
+    _ptr_window.with_owned_return(std::ptr::null_mut(), |window| match window {
+        GlutinWindowedContext::Mock(window) => {
+            ValueBox::new(StringBox::from_string(window.state().title)).into_raw()
+        }
+        _ => std::ptr::null_mut(),
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_mock_window_is_cursor_visible(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| match window {
+        GlutinWindowedContext::Mock(window) => window.state().cursor_visible,
+        _ => false,
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_mock_window_is_cursor_grabbed(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| match window {
+        GlutinWindowedContext::Mock(window) => window.state().cursor_grabbed,
+        _ => false,
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_mock_window_is_minimized(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| match window {
+        GlutinWindowedContext::Mock(window) => window.state().minimized,
+        _ => false,
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_mock_window_is_visible(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| match window {
+        GlutinWindowedContext::Mock(window) => window.state().visible,
+        _ => false,
+    })
+}
+
+/// Mock event loops and windows for the tests of the modules that drive an event loop
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use geometry_box::U128Box;
+    use value_box::ValueBox;
+
+    use crate::context_builder::{glutin_context_builder_default, glutin_destroy_context_builder};
+    use crate::event_loop::{
+        glutin_create_events_loop_with_backend, GlutinBackendType, GlutinEventLoop,
+    };
+    use crate::window_builder::{glutin_create_window_builder, glutin_destroy_window_builder};
+    use crate::windowed_context::{
+        glutin_create_windowed_context, glutin_windowed_context_get_id, GlutinWindowedContext,
+    };
+
+    pub(crate) fn create_mock_event_loop() -> *mut ValueBox<GlutinEventLoop> {
+        glutin_create_events_loop_with_backend(GlutinBackendType::Mock)
+    }
+
+    pub(crate) fn create_mock_window(
+        event_loop: *mut ValueBox<GlutinEventLoop>,
+    ) -> *mut ValueBox<GlutinWindowedContext> {
+        let window_builder = glutin_create_window_builder();
+        let context_builder = glutin_context_builder_default();
+        let window = glutin_create_windowed_context(event_loop, window_builder, context_builder);
+        glutin_destroy_window_builder(window_builder);
+        glutin_destroy_context_builder(context_builder);
+        window
+    }
+
+    pub(crate) fn window_id(
+        window: *mut ValueBox<GlutinWindowedContext>,
+    ) -> *mut ValueBox<U128Box> {
+        let id = ValueBox::new(U128Box::default()).into_raw();
+        glutin_windowed_context_get_id(window, id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry_box::{PointBox, SizeBox};
+    use string_box::StringBox;
+    use value_box::{ValueBox, ValueBoxPointer};
+
+    use crate::cursor::{glutin_create_custom_cursor, glutin_destroy_custom_cursor};
+    use crate::enums::GlutinResult;
+    use crate::event_injection::*;
+    use crate::event_loop::*;
+    use crate::events::*;
+    use crate::windowed_context::*;
+
+    use super::fixtures::*;
+    use super::{
+        glutin_mock_window_get_title, glutin_mock_window_is_cursor_visible,
+        glutin_windowed_context_is_mock,
+    };
+
+    #[test]
+    fn window_accessors_change_mock_window() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        assert!(glutin_windowed_context_is_mock(window));
+
+        let title = ValueBox::new(StringBox::from_string(String::from("Mock"))).into_raw();
+        glutin_windowed_context_set_title(window, title);
+        title.release();
+        let title = glutin_mock_window_get_title(window);
+        assert_eq!(
+            title.with_not_null_return(String::new(), |title| title.to_string()),
+            "Mock"
+        );
+        title.release();
+
+        glutin_windowed_context_set_inner_size(window, 320, 240);
+        let size = ValueBox::new(SizeBox::<u32>::default()).into_raw();
+        glutin_windowed_context_get_inner_size(window, size);
+        assert_eq!(
+            size.with_not_null_return((0, 0), |size| (size.width, size.height)),
+            (320, 240)
+        );
+        size.release();
+
+        glutin_windowed_context_set_position(window, 10, 20);
+        let position = ValueBox::new(PointBox::<i32>::default()).into_raw();
+        glutin_windowed_context_get_position(window, position);
+        assert_eq!(
+            position.with_not_null_return((0, 0), |point| (point.x, point.y)),
+            (10, 20)
+        );
+        position.release();
+
+        glutin_windowed_context_set_maximized(window, true);
+        assert!(glutin_windowed_context_is_maximized(window));
+
+        assert!(!glutin_windowed_context_is_fullscreen(window));
+        glutin_windowed_context_set_fullscreen(window, true);
+        assert!(glutin_windowed_context_is_fullscreen(window));
+
+        glutin_windowed_context_set_cursor_visible(window, false);
+        assert!(!glutin_mock_window_is_cursor_visible(window));
+
+        let pixels = [0u8; 4];
+        let cursor = glutin_create_custom_cursor(pixels.as_ptr(), pixels.len(), 1, 1, 0, 0);
+        assert_eq!(
+            glutin_windowed_context_set_custom_cursor(window, cursor),
+            GlutinResult::NotSupported
+        );
+        assert_eq!(
+            glutin_windowed_context_set_custom_cursor(window, std::ptr::null_mut()),
+            GlutinResult::NullPointer
+        );
+        glutin_destroy_custom_cursor(cursor);
+
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn mock_windows_are_not_read_by_other_threads() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+
+        // raw pointers are not Send, the test passes them as addresses
+        let thread_window = window as usize;
+        let (is_mock, title) = std::thread::spawn(move || {
+            let window = thread_window as *mut ValueBox<GlutinWindowedContext>;
+            (
+                glutin_windowed_context_is_mock(window),
+                glutin_mock_window_get_title(window) as usize,
+            )
+        })
+        .join()
+        .unwrap();
+        assert!(!is_mock);
+        assert_eq!(title, 0);
+        assert!(glutin_windowed_context_is_mock(window));
+
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn mock_windows_have_distinct_ids() {
+        let event_loop = create_mock_event_loop();
+        let first_window = create_mock_window(event_loop);
+        let second_window = create_mock_window(event_loop);
+
+        let first_id = window_id(first_window);
+        let second_id = window_id(second_window);
+        let first_low = first_id.with_not_null_return(0, |id| id.low);
+        let second_low = second_id.with_not_null_return(0, |id| id.low);
+        assert_ne!(first_low, second_low);
+
+        first_id.release();
+        second_id.release();
+        glutin_destroy_windowed_context(first_window);
+        glutin_destroy_windowed_context(second_window);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn event_loop_delivers_injected_events() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let id = window_id(window);
+
+        assert_eq!(
+            glutin_events_loop_inject_cursor_moved(event_loop, id, 5.0, 6.0),
+            GlutinResult::Ok
+        );
+        assert_eq!(
+            glutin_events_loop_inject_close_requested(event_loop, id),
+            GlutinResult::Ok
+        );
+
+        let mut event_types = vec![];
+        let mut synthetic_window_ids = vec![];
+        event_loop.with_not_null(|event_loop| {
+            event_loop.run_events(|c_event| {
+                if c_event.is_synthetic {
+                    event_types.push(c_event.event_type);
+                    synthetic_window_ids.push(c_event.window_id.low);
+                }
+                match c_event.event_type {
+                    GlutinEventType::WindowEventCloseRequested => GlutinControlFlow::Exit,
+                    _ => GlutinControlFlow::Wait,
+                }
+            })
+        });
+
+        assert_eq!(
+            event_types,
+            vec![
+                GlutinEventType::WindowEventCursorMoved,
+                GlutinEventType::WindowEventCloseRequested
+            ]
+        );
+        let low = id.with_not_null_return(0, |id| id.low);
+        assert!(synthetic_window_ids
+            .iter()
+            .all(|window_id| *window_id == low));
+
+        id.release();
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+
+    #[test]
+    fn event_loop_delivers_redraw_requests() {
+        let event_loop = create_mock_event_loop();
+        let window = create_mock_window(event_loop);
+        let id = window_id(window);
+        glutin_windowed_context_request_redraw(window);
+
+        let mut redrawn_window_ids = vec![];
+        event_loop.with_not_null(|event_loop| {
+            event_loop.pump_events(std::time::Duration::ZERO, |c_event| {
+                if c_event.event_type == GlutinEventType::RedrawRequested {
+                    redrawn_window_ids.push(c_event.window_id.low);
+                }
+                GlutinControlFlow::Poll
+            });
+        });
+
+        assert_eq!(
+            redrawn_window_ids,
+            vec![id.with_not_null_return(0, |id| id.low)]
+        );
+
+        id.release();
+        glutin_destroy_windowed_context(window);
+        glutin_destroy_events_loop(event_loop);
+    }
+}
@@ -2,7 +2,7 @@ use glutin::PixelFormat;
 use value_box::{ValueBox, ValueBoxPointer};
 
 #[no_mangle]
-pub fn glutin_pixel_format_default() -> *mut ValueBox<PixelFormat> {
+pub extern "C" fn glutin_pixel_format_default() -> *mut ValueBox<PixelFormat> {
     ValueBox::new(PixelFormat {
         hardware_accelerated: false,
         color_bits: 0,
@@ -18,56 +18,58 @@ pub fn glutin_pixel_format_default() -> *mut ValueBox<PixelFormat> {
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_is_hardware_accelerated(_ptr: *mut ValueBox<PixelFormat>) -> bool {
+pub extern "C" fn glutin_pixel_format_is_hardware_accelerated(
+    _ptr: *mut ValueBox<PixelFormat>,
+) -> bool {
     _ptr.with_not_null_return(false, |pixel_format| pixel_format.hardware_accelerated)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_get_color_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
+pub extern "C" fn glutin_pixel_format_get_color_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
     _ptr.with_not_null_return(0, |pixel_format| pixel_format.color_bits)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_get_alpha_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
+pub extern "C" fn glutin_pixel_format_get_alpha_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
     _ptr.with_not_null_return(0, |pixel_format| pixel_format.alpha_bits)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_get_depth_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
+pub extern "C" fn glutin_pixel_format_get_depth_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
     _ptr.with_not_null_return(0, |pixel_format| pixel_format.depth_bits)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_get_stencil_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
+pub extern "C" fn glutin_pixel_format_get_stencil_bits(_ptr: *mut ValueBox<PixelFormat>) -> u8 {
     _ptr.with_not_null_return(0, |pixel_format| pixel_format.stencil_bits)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_is_stereoscopy(_ptr: *mut ValueBox<PixelFormat>) -> bool {
+pub extern "C" fn glutin_pixel_format_is_stereoscopy(_ptr: *mut ValueBox<PixelFormat>) -> bool {
     _ptr.with_not_null_return(false, |pixel_format| pixel_format.stereoscopy)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_is_double_buffer(_ptr: *mut ValueBox<PixelFormat>) -> bool {
+pub extern "C" fn glutin_pixel_format_is_double_buffer(_ptr: *mut ValueBox<PixelFormat>) -> bool {
     _ptr.with_not_null_return(false, |pixel_format| pixel_format.double_buffer)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_has_multisampling(_ptr: *mut ValueBox<PixelFormat>) -> bool {
+pub extern "C" fn glutin_pixel_format_has_multisampling(_ptr: *mut ValueBox<PixelFormat>) -> bool {
     _ptr.with_not_null_return(false, |pixel_format| pixel_format.multisampling.is_some())
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_get_multisampling(_ptr: *mut ValueBox<PixelFormat>) -> u16 {
+pub extern "C" fn glutin_pixel_format_get_multisampling(_ptr: *mut ValueBox<PixelFormat>) -> u16 {
     _ptr.with_not_null_return(0, |pixel_format| pixel_format.multisampling.unwrap_or(0))
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_is_srgb(_ptr: *mut ValueBox<PixelFormat>) -> bool {
+pub extern "C" fn glutin_pixel_format_is_srgb(_ptr: *mut ValueBox<PixelFormat>) -> bool {
     _ptr.with_not_null_return(false, |pixel_format| pixel_format.srgb)
 }
 
 #[no_mangle]
-pub fn glutin_pixel_format_drop(_ptr: *mut ValueBox<PixelFormat>) {
+pub extern "C" fn glutin_pixel_format_drop(_ptr: *mut ValueBox<PixelFormat>) {
     _ptr.release();
 }
@@ -0,0 +1,212 @@
+#![cfg(target_os = "linux")]
+
+use std::os::raw::c_uint;
+use std::sync::Mutex;
+
+use glutin::event_loop::EventLoop;
+use glutin::platform::unix::{EventLoopWindowTargetExtUnix, WindowBuilderExtUnix, XWindowType};
+use glutin::window::WindowBuilder;
+use string_box::StringBox;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use x11_dl::error::OpenError;
+use x11_dl::xcursor::Xcursor;
+use x11_dl::xlib::{KeySym, Success, XkbStateRec, Xlib};
+
+use crate::enums::GlutinX11WindowType;
+use crate::event_loop::GlutinLoopEvent;
+use crate::events::{GlutinLockKeys, GlutinLockKeysQuery, GlutinLogicalCharacterQuery};
+
+/// Selects the core keyboard in XKB requests
+const XKB_USE_CORE_KEYBOARD: c_uint = 0x0100;
+/// The standard XKB keyboard indicators of the lock keys
+const CAPS_LOCK_INDICATOR: c_uint = 1 << 0;
+const NUM_LOCK_INDICATOR: c_uint = 1 << 1;
+/// winit reports X11 keycodes shifted down by the minimum keycode of evdev
+const X11_KEYCODE_OFFSET: u32 = 8;
+/// Keysyms of the unicode characters outside of latin-1 are the code point plus this offset
+const UNICODE_KEYSYM_OFFSET: KeySym = 0x0100_0000;
+
+static XLIB: Mutex<Option<&'static Xlib>> = Mutex::new(None);
+static XCURSOR: Mutex<Option<&'static Xcursor>> = Mutex::new(None);
+
+/// Load a library the first time it is needed and keep it for the lifetime of the process,
+/// the X11 resources created with it may outlive any single caller
+fn glutin_load_library<T>(
+    library: &Mutex<Option<&'static T>>,
+    name: &str,
+    open: fn() -> Result<T, OpenError>,
+) -> Result<&'static T, String> {
+    let mut library = library
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match *library {
+        Some(library) => Ok(library),
+        None => {
+            let loaded: &'static T = Box::leak(Box::new(
+                open().map_err(|error| format!("Could not load {}: {}", name, error))?,
+            ));
+            *library = Some(loaded);
+            Ok(loaded)
+        }
+    }
+}
+
+pub fn glutin_xlib() -> Result<&'static Xlib, String> {
+    glutin_load_library(&XLIB, "Xlib", Xlib::open)
+}
+
+pub fn glutin_xcursor() -> Result<&'static Xcursor, String> {
+    glutin_load_library(&XCURSOR, "Xcursor", Xcursor::open)
+}
+
+/// Read the lock keys from the keyboard indicators of the X11 display of the event loop.
+/// Wayland only reports the lock state with its keyboard events, which winit does not expose.
+pub fn glutin_lock_keys_query(
+    event_loop: &EventLoop<GlutinLoopEvent>,
+) -> Option<GlutinLockKeysQuery> {
+    let connection = event_loop.xlib_xconnection()?;
+    Some(Box::new(move || {
+        let mut indicators: c_uint = 0;
+        let status = unsafe {
+            (connection.xlib.XkbGetIndicatorState)(
+                connection.display,
+                XKB_USE_CORE_KEYBOARD,
+                &mut indicators,
+            )
+        };
+        if status != Success as i32 {
+            return None;
+        }
+        Some(GlutinLockKeys {
+            caps_lock: indicators & CAPS_LOCK_INDICATOR != 0,
+            num_lock: indicators & NUM_LOCK_INDICATOR != 0,
+        })
+    }))
+}
+
+/// Read the unmodified character of a key from the active layout group of the X11 display of
+/// the event loop, so that shortcuts follow the layout instead of the key positions.
+pub fn glutin_logical_character_query(
+    event_loop: &EventLoop<GlutinLoopEvent>,
+) -> Option<GlutinLogicalCharacterQuery> {
+    let connection = event_loop.xlib_xconnection()?;
+    Some(Box::new(move |scan_code| {
+        let keycode = u8::try_from(scan_code.checked_add(X11_KEYCODE_OFFSET)?).ok()?;
+        let mut state: XkbStateRec = unsafe { std::mem::zeroed() };
+        let status = unsafe {
+            (connection.xlib.XkbGetState)(connection.display, XKB_USE_CORE_KEYBOARD, &mut state)
+        };
+        if status != Success as i32 {
+            return None;
+        }
+        let keysym = unsafe {
+            (connection.xlib.XkbKeycodeToKeysym)(connection.display, keycode, state.group as i32, 0)
+        };
+        glutin_keysym_character(keysym)
+    }))
+}
+
+/// Latin-1 keysyms are their own code points and the rest of unicode is offset,
+/// the other keysyms name keys that do not produce a character
+fn glutin_keysym_character(keysym: KeySym) -> Option<char> {
+    match keysym {
+        0x20..=0x7e | 0xa0..=0xff => char::from_u32(keysym as u32),
+        keysym if keysym > UNICODE_KEYSYM_OFFSET => u32::try_from(keysym - UNICODE_KEYSYM_OFFSET)
+            .ok()
+            .and_then(char::from_u32),
+        _ => None,
+    }
+}
+
+impl From<GlutinX11WindowType> for XWindowType {
+    fn from(window_type: GlutinX11WindowType) -> XWindowType {
+        match window_type {
+            GlutinX11WindowType::Normal => XWindowType::Normal,
+            GlutinX11WindowType::Desktop => XWindowType::Desktop,
+            GlutinX11WindowType::Dock => XWindowType::Dock,
+            GlutinX11WindowType::Toolbar => XWindowType::Toolbar,
+            GlutinX11WindowType::Menu => XWindowType::Menu,
+            GlutinX11WindowType::Utility => XWindowType::Utility,
+            GlutinX11WindowType::Splash => XWindowType::Splash,
+            GlutinX11WindowType::Dialog => XWindowType::Dialog,
+            GlutinX11WindowType::DropdownMenu => XWindowType::DropdownMenu,
+            GlutinX11WindowType::PopupMenu => XWindowType::PopupMenu,
+            GlutinX11WindowType::Tooltip => XWindowType::Tooltip,
+            GlutinX11WindowType::Notification => XWindowType::Notification,
+            GlutinX11WindowType::Combo => XWindowType::Combo,
+            GlutinX11WindowType::Dnd => XWindowType::Dnd,
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_full_size(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _with_full_size: bool,
+) {
+}
+
+/// Set the X11 `WM_CLASS` hint, defaults to the name of the binary.
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_class(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _ptr_class: *mut ValueBox<StringBox>,
+    _ptr_instance: *mut ValueBox<StringBox>,
+) {
+    _ptr_class.with_not_null(|class| {
+        _ptr_instance.with_not_null(|instance| {
+            _ptr_window_builder
+                .replace_value(|builder| {
+                    builder.with_class(instance.to_string(), class.to_string())
+                })
+                .log();
+        })
+    });
+}
+
+/// Set both the class and the instance of the X11 `WM_CLASS` hint to the same name.
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_name(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _ptr_name: *mut ValueBox<StringBox>,
+) {
+    _ptr_name.with_not_null(|name| {
+        _ptr_window_builder
+            .replace_value(|builder| builder.with_class(name.to_string(), name.to_string()))
+            .log();
+    });
+}
+
+/// Set the Wayland application id. It should match the `.desktop` file of the application.
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_app_id(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _ptr_app_id: *mut ValueBox<StringBox>,
+) {
+    _ptr_app_id.with_not_null(|app_id| {
+        _ptr_window_builder
+            .replace_value(|builder| builder.with_app_id(app_id.to_string()))
+            .log();
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_x11_window_type(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _window_type: GlutinX11WindowType,
+) {
+    _ptr_window_builder
+        .replace_value(|builder| builder.with_x11_window_type(vec![_window_type.into()]))
+        .log();
+}
+
+/// Override-redirect windows are not managed by the X11 window manager.
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_override_redirect(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _with_override_redirect: bool,
+) {
+    _ptr_window_builder
+        .replace_value(|builder| builder.with_override_redirect(_with_override_redirect))
+        .log();
+}
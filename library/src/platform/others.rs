@@ -1,9 +1,64 @@
+use glutin::event_loop::EventLoop;
 use glutin::window::WindowBuilder;
+use string_box::StringBox;
 use value_box::ValueBox;
 
+use crate::enums::GlutinX11WindowType;
+use crate::event_loop::GlutinLoopEvent;
+use crate::events::{GlutinLockKeysQuery, GlutinLogicalCharacterQuery};
+
 #[no_mangle]
-pub fn glutin_window_builder_with_full_size(
+pub extern "C" fn glutin_window_builder_with_full_size(
     _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     _with_full_size: bool,
 ) {
 }
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_class(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _ptr_class: *mut ValueBox<StringBox>,
+    _ptr_instance: *mut ValueBox<StringBox>,
+) {
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_name(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _ptr_name: *mut ValueBox<StringBox>,
+) {
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_app_id(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _ptr_app_id: *mut ValueBox<StringBox>,
+) {
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_x11_window_type(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _window_type: GlutinX11WindowType,
+) {
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_window_builder_with_override_redirect(
+    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
+    _with_override_redirect: bool,
+) {
+}
+
+/// The lock keys are tracked from key presses on this platform
+pub fn glutin_lock_keys_query(
+    _event_loop: &EventLoop<GlutinLoopEvent>,
+) -> Option<GlutinLockKeysQuery> {
+    None
+}
+
+pub fn glutin_logical_character_query(
+    _event_loop: &EventLoop<GlutinLoopEvent>,
+) -> Option<GlutinLogicalCharacterQuery> {
+    None
+}
@@ -0,0 +1,186 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread::ThreadId;
+
+use value_box::{ValueBox, ValueBoxPointer};
+
+use crate::enums::GlutinResult;
+
+/// The thread that created a boxed value and an identity that, unlike the address
+/// of the box, is never reused by another value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct GlutinOwner {
+    thread: ThreadId,
+    identity: u64,
+}
+
+/// The owners of the live event loops and windows by the address of their box. The owner is
+/// kept outside of the box, so that other threads are refused without reading the value.
+static OWNERS: Mutex<BTreeMap<usize, GlutinOwner>> = Mutex::new(BTreeMap::new());
+
+static NEXT_IDENTITY: AtomicU64 = AtomicU64::new(1);
+
+fn owners() -> MutexGuard<'static, BTreeMap<usize, GlutinOwner>> {
+    match OWNERS.lock() {
+        Ok(owners) => owners,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Access to a boxed value that is refused on threads that do not own it.
+/// Event loops and windows belong to the thread that created them.
+pub trait GlutinThreadGuardedPointer<T> {
+    /// Make the current thread the owner of a newly boxed value
+    fn owned_by_current_thread(self) -> Self;
+
+    /// The identity of the value, or None if it was destroyed or has no owner
+    fn owned_identity(&self) -> Option<u64>;
+
+    /// Return `Ok` if the value can be used by the current thread
+    fn check_owner_thread(&self) -> GlutinResult;
+
+    /// Evaluate the block with the value, or return the default value if the pointer is null
+    /// or the value belongs to another thread
+    fn with_owned_return<R>(&self, default: R, block: impl FnOnce(&mut T) -> R) -> R;
+
+    /// Evaluate the block with the value, returning `NullPointer` or `WrongThread`
+    /// if the value can not be used
+    fn with_owned_result(&self, block: impl FnOnce(&mut T) -> GlutinResult) -> GlutinResult;
+
+    /// Release the value unless it belongs to another thread, which would leave the
+    /// windowing system in an undefined state. The value is leaked in that case.
+    fn release_owned(self) -> GlutinResult;
+}
+
+impl<T: Any> GlutinThreadGuardedPointer<T> for *mut ValueBox<T> {
+    fn owned_by_current_thread(self) -> Self {
+        if !self.is_null() {
+            let owner = GlutinOwner {
+                thread: std::thread::current().id(),
+                identity: NEXT_IDENTITY.fetch_add(1, Ordering::Relaxed),
+            };
+            owners().insert(self as usize, owner);
+        }
+        self
+    }
+
+    fn owned_identity(&self) -> Option<u64> {
+        owners().get(&(*self as usize)).map(|owner| owner.identity)
+    }
+
+    fn check_owner_thread(&self) -> GlutinResult {
+        if self.is_null() {
+            return GlutinResult::NullPointer;
+        }
+        let owner = owners().get(&(*self as usize)).copied();
+        match owner {
+            Some(owner) if owner.thread == std::thread::current().id() => GlutinResult::Ok,
+            Some(_) => {
+                error!(
+                    "{} is used by {:?} which is not the thread that created it",
+                    std::any::type_name::<T>(),
+                    std::thread::current().id()
+                );
+                GlutinResult::WrongThread
+            }
+            None => {
+                error!(
+                    "{} is destroyed or was not created by the library",
+                    std::any::type_name::<T>()
+                );
+                GlutinResult::NullPointer
+            }
+        }
+    }
+
+    fn with_owned_return<R>(&self, default: R, block: impl FnOnce(&mut T) -> R) -> R {
+        match self.check_owner_thread() {
+            GlutinResult::Ok => self.with_not_null_return(default, block),
+            _ => default,
+        }
+    }
+
+    fn with_owned_result(&self, block: impl FnOnce(&mut T) -> GlutinResult) -> GlutinResult {
+        match self.check_owner_thread() {
+            GlutinResult::Ok => self.with_not_null_return(GlutinResult::NullPointer, block),
+            result => result,
+        }
+    }
+
+    fn release_owned(self) -> GlutinResult {
+        let result = self.check_owner_thread();
+        if result == GlutinResult::Ok {
+            owners().remove(&(self as usize));
+            self.release();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run the block on another thread, raw pointers are not Send so they are passed as addresses
+    fn on_other_thread<R: Send + 'static>(
+        pointer: *mut ValueBox<u32>,
+        block: impl FnOnce(*mut ValueBox<u32>) -> R + Send + 'static,
+    ) -> R {
+        let address = pointer as usize;
+        std::thread::spawn(move || block(address as *mut ValueBox<u32>))
+            .join()
+            .unwrap()
+    }
+
+    #[test]
+    fn owned_values_are_refused_on_other_threads() {
+        let value = ValueBox::new(42u32).into_raw().owned_by_current_thread();
+
+        assert_eq!(value.check_owner_thread(), GlutinResult::Ok);
+        assert_eq!(value.with_owned_return(0, |value| *value), 42);
+        assert_eq!(
+            on_other_thread(value, |value| value.check_owner_thread()),
+            GlutinResult::WrongThread
+        );
+        assert_eq!(
+            on_other_thread(value, |value| value.with_owned_return(0, |value| *value)),
+            0
+        );
+        assert_eq!(
+            on_other_thread(value, |value| value.release_owned()),
+            GlutinResult::WrongThread
+        );
+
+        // the refused release left the value alive and registered
+        assert_eq!(value.with_owned_return(0, |value| *value), 42);
+        assert_eq!(value.release_owned(), GlutinResult::Ok);
+    }
+
+    #[test]
+    fn released_and_unregistered_values_are_null() {
+        let unregistered = ValueBox::new(1u32).into_raw();
+        assert_eq!(unregistered.check_owner_thread(), GlutinResult::NullPointer);
+        assert_eq!(unregistered.owned_identity(), None);
+        unregistered.release();
+
+        let null: *mut ValueBox<u32> = std::ptr::null_mut();
+        assert_eq!(
+            null.with_owned_result(|_| GlutinResult::Ok),
+            GlutinResult::NullPointer
+        );
+
+        let value = ValueBox::new(2u32).into_raw().owned_by_current_thread();
+        let identity = value.owned_identity();
+        assert!(identity.is_some());
+        assert_eq!(value.release_owned(), GlutinResult::Ok);
+        assert_eq!(value.owned_identity(), None);
+        assert_eq!(value.check_owner_thread(), GlutinResult::NullPointer);
+
+        // a new value at the same address is a different value
+        let new_value = ValueBox::new(3u32).into_raw().owned_by_current_thread();
+        assert_ne!(new_value.owned_identity(), identity);
+        assert_eq!(new_value.release_owned(), GlutinResult::Ok);
+    }
+}
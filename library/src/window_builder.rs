@@ -4,17 +4,17 @@ use string_box::StringBox;
 use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
 
 #[no_mangle]
-pub fn glutin_create_window_builder() -> *mut ValueBox<WindowBuilder> {
+pub extern "C" fn glutin_create_window_builder() -> *mut ValueBox<WindowBuilder> {
     ValueBox::new(WindowBuilder::new()).into_raw()
 }
 
 #[no_mangle]
-pub fn glutin_destroy_window_builder(_ptr: *mut ValueBox<WindowBuilder>) {
+pub extern "C" fn glutin_destroy_window_builder(_ptr: *mut ValueBox<WindowBuilder>) {
     _ptr.release();
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_title(
+pub extern "C" fn glutin_window_builder_with_title(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     _ptr_boxer_string: *mut ValueBox<StringBox>,
 ) {
@@ -26,7 +26,7 @@ pub fn glutin_window_builder_with_title(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_decorations(
+pub extern "C" fn glutin_window_builder_with_decorations(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     with_decorations: bool,
 ) {
@@ -36,7 +36,7 @@ pub fn glutin_window_builder_with_decorations(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_transparency(
+pub extern "C" fn glutin_window_builder_with_transparency(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     with_transparency: bool,
 ) {
@@ -46,7 +46,7 @@ pub fn glutin_window_builder_with_transparency(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_resizable(
+pub extern "C" fn glutin_window_builder_with_resizable(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     with_resizable: bool,
 ) {
@@ -56,7 +56,7 @@ pub fn glutin_window_builder_with_resizable(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_dimensions(
+pub extern "C" fn glutin_window_builder_with_dimensions(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     width: f64,
     height: f64,
@@ -67,7 +67,7 @@ pub fn glutin_window_builder_with_dimensions(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_maximized(
+pub extern "C" fn glutin_window_builder_with_maximized(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     with_maximized: bool,
 ) {
@@ -77,7 +77,7 @@ pub fn glutin_window_builder_with_maximized(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_visibility(
+pub extern "C" fn glutin_window_builder_with_visibility(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     with_visibility: bool,
 ) {
@@ -87,7 +87,7 @@ pub fn glutin_window_builder_with_visibility(
 }
 
 #[no_mangle]
-pub fn glutin_window_builder_with_always_on_top(
+pub extern "C" fn glutin_window_builder_with_always_on_top(
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     with_always_on_top: bool,
 ) {
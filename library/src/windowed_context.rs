@@ -2,23 +2,32 @@ use std::os::raw::c_void;
 
 use geometry_box::{PointBox, SizeBox, U128Box};
 use glutin::dpi::{PhysicalPosition, PhysicalSize};
-use glutin::window::Window;
+use glutin::error::{ExternalError, NotSupportedError};
 use glutin::window::WindowBuilder;
+use glutin::window::{CursorIcon, Fullscreen, UserAttentionType, Window};
 use glutin::{Api, ContextError, NotCurrent, PixelFormat, PossiblyCurrent, WindowedContext};
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 use string_box::StringBox;
 use value_box::{BoxerError, ReturnBoxerResult, ValueBox, ValueBoxPointer};
 
 use crate::context_builder::GlutinContextBuilder;
-use crate::enums::GlutinCursorIcon;
-use crate::event_loop::GlutinEventLoop;
+use crate::cursor::{
+    glutin_is_custom_cursor_supported, glutin_release_window_cursors, glutin_set_cursor_icon,
+    GlutinCustomCursor,
+};
+use crate::enums::{GlutinCursorIcon, GlutinResult, GlutinUserAttentionType};
+use crate::event_loop::{GlutinEventLoop, GlutinEventLoopBackend};
+use crate::mock::MockWindow;
 use crate::pixel_format::glutin_pixel_format_default;
+use crate::thread_guard::GlutinThreadGuardedPointer;
 use crate::{glutin_convert_window_id, ContextApi};
 
 #[derive(Debug)]
 pub enum GlutinWindowedContext {
     NotCurrent(WindowedContext<NotCurrent>),
     PossiblyCurrent(WindowedContext<PossiblyCurrent>),
+    /// An in-memory window of the mock backend without a rendering context
+    Mock(MockWindow),
 }
 
 impl GlutinWindowedContext {
@@ -28,6 +37,7 @@ impl GlutinWindowedContext {
                 .map_err(|error| (GlutinWindowedContext::NotCurrent(error.0), error.1)),
             GlutinWindowedContext::PossiblyCurrent(context) => (unsafe { context.make_current() })
                 .map_err(|error| (GlutinWindowedContext::PossiblyCurrent(error.0), error.1)),
+            GlutinWindowedContext::Mock(window) => return Ok(GlutinWindowedContext::Mock(window)),
         })
         .map(|context| GlutinWindowedContext::PossiblyCurrent(context))
     }
@@ -36,6 +46,7 @@ impl GlutinWindowedContext {
         match self {
             GlutinWindowedContext::NotCurrent(_) => Err(ContextError::FunctionUnavailable),
             GlutinWindowedContext::PossiblyCurrent(context) => context.swap_buffers(),
+            GlutinWindowedContext::Mock(_) => Ok(()),
         }
     }
 
@@ -46,20 +57,21 @@ impl GlutinWindowedContext {
                 std::ptr::null()
             }
             GlutinWindowedContext::PossiblyCurrent(context) => context.get_proc_address(addr),
+            GlutinWindowedContext::Mock(_) => std::ptr::null(),
         }
     }
 
-    pub fn raw_window_handle(&self) -> RawWindowHandle {
-        match self {
-            GlutinWindowedContext::NotCurrent(context) => context.window().raw_window_handle(),
-            GlutinWindowedContext::PossiblyCurrent(context) => context.window().raw_window_handle(),
-        }
+    pub fn raw_window_handle(&self) -> Option<RawWindowHandle> {
+        self.window()
+            .native_window()
+            .map(|window| window.raw_window_handle())
     }
 
-    pub fn window(&self) -> &Window {
+    pub fn window(&self) -> &dyn GlutinWindow {
         match self {
             GlutinWindowedContext::NotCurrent(context) => context.window(),
             GlutinWindowedContext::PossiblyCurrent(context) => context.window(),
+            GlutinWindowedContext::Mock(window) => window,
         }
     }
 
@@ -67,6 +79,7 @@ impl GlutinWindowedContext {
         match self {
             GlutinWindowedContext::NotCurrent(context) => context.get_api(),
             GlutinWindowedContext::PossiblyCurrent(context) => context.get_api(),
+            GlutinWindowedContext::Mock(_) => Api::OpenGl,
         }
     }
 
@@ -74,6 +87,7 @@ impl GlutinWindowedContext {
         match self {
             GlutinWindowedContext::NotCurrent(context) => context.is_current(),
             GlutinWindowedContext::PossiblyCurrent(context) => context.is_current(),
+            GlutinWindowedContext::Mock(_) => true,
         }
     }
 
@@ -84,6 +98,7 @@ impl GlutinWindowedContext {
                 None
             }
             GlutinWindowedContext::PossiblyCurrent(context) => Some(context.get_pixel_format()),
+            GlutinWindowedContext::Mock(_) => None,
         }
     }
 
@@ -91,12 +106,151 @@ impl GlutinWindowedContext {
         match self {
             GlutinWindowedContext::NotCurrent(_) => error!("Unable to resize not current context"),
             GlutinWindowedContext::PossiblyCurrent(context) => context.resize(size),
+            GlutinWindowedContext::Mock(_) => {}
         }
     }
 }
 
+/// Window operations shared by native windows and the in-memory windows of the mock backend
+pub trait GlutinWindow {
+    /// The native window, or None for mock windows
+    fn native_window(&self) -> Option<&Window>;
+    fn window_id(&self) -> U128Box;
+    fn request_redraw(&self);
+    fn scale_factor(&self) -> f64;
+    fn inner_size(&self) -> PhysicalSize<u32>;
+    fn set_inner_size(&self, size: PhysicalSize<u32>);
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError>;
+    fn set_outer_position(&self, position: PhysicalPosition<i32>);
+    fn set_title(&self, title: &str);
+    fn set_cursor_icon(&self, cursor: CursorIcon);
+    fn set_cursor_visible(&self, visible: bool);
+    fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError>;
+    fn set_cursor_position(&self, position: PhysicalPosition<f64>) -> Result<(), ExternalError>;
+    fn set_maximized(&self, maximized: bool);
+    fn is_maximized(&self) -> bool;
+    fn set_fullscreen(&self, fullscreen: bool);
+    fn is_fullscreen(&self) -> bool;
+    fn set_minimized(&self, minimized: bool);
+    fn set_visible(&self, visible: bool);
+    fn set_ime_position(&self, position: PhysicalPosition<i32>);
+    fn focus_window(&self);
+    fn request_user_attention(&self, request_type: Option<UserAttentionType>);
+    fn drag_window(&self) -> Result<(), ExternalError>;
+    fn set_decorations(&self, decorations: bool);
+    fn set_resizable(&self, resizable: bool);
+    fn set_always_on_top(&self, always_on_top: bool);
+}
+
+impl GlutinWindow for Window {
+    fn native_window(&self) -> Option<&Window> {
+        Some(self)
+    }
+
+    fn window_id(&self) -> U128Box {
+        glutin_convert_window_id(self.id())
+    }
+
+    fn request_redraw(&self) {
+        Window::request_redraw(self)
+    }
+
+    fn scale_factor(&self) -> f64 {
+        Window::scale_factor(self)
+    }
+
+    fn inner_size(&self) -> PhysicalSize<u32> {
+        Window::inner_size(self)
+    }
+
+    fn set_inner_size(&self, size: PhysicalSize<u32>) {
+        Window::set_inner_size(self, size)
+    }
+
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        Window::outer_position(self)
+    }
+
+    fn set_outer_position(&self, position: PhysicalPosition<i32>) {
+        Window::set_outer_position(self, position)
+    }
+
+    fn set_title(&self, title: &str) {
+        Window::set_title(self, title)
+    }
+
+    fn set_cursor_icon(&self, cursor: CursorIcon) {
+        glutin_set_cursor_icon(self, cursor)
+    }
+
+    fn set_cursor_visible(&self, visible: bool) {
+        Window::set_cursor_visible(self, visible)
+    }
+
+    fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
+        Window::set_cursor_grab(self, grab)
+    }
+
+    fn set_cursor_position(&self, position: PhysicalPosition<f64>) -> Result<(), ExternalError> {
+        Window::set_cursor_position(self, position)
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        Window::set_maximized(self, maximized)
+    }
+
+    fn is_maximized(&self) -> bool {
+        Window::is_maximized(self)
+    }
+
+    fn set_fullscreen(&self, fullscreen: bool) {
+        // borderless on the current monitor, exclusive video modes are not exposed
+        Window::set_fullscreen(self, fullscreen.then_some(Fullscreen::Borderless(None)))
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        Window::fullscreen(self).is_some()
+    }
+
+    fn set_minimized(&self, minimized: bool) {
+        Window::set_minimized(self, minimized)
+    }
+
+    fn set_visible(&self, visible: bool) {
+        Window::set_visible(self, visible)
+    }
+
+    fn set_ime_position(&self, position: PhysicalPosition<i32>) {
+        Window::set_ime_position(self, position)
+    }
+
+    fn focus_window(&self) {
+        Window::focus_window(self)
+    }
+
+    fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
+        Window::request_user_attention(self, request_type)
+    }
+
+    fn drag_window(&self) -> Result<(), ExternalError> {
+        Window::drag_window(self)
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        Window::set_decorations(self, decorations)
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        Window::set_resizable(self, resizable)
+    }
+
+    fn set_always_on_top(&self, always_on_top: bool) {
+        Window::set_always_on_top(self, always_on_top)
+    }
+}
+
 #[no_mangle]
-pub fn glutin_create_windowed_context(
+pub extern "C" fn glutin_create_windowed_context(
     _ptr_events_loop: *mut ValueBox<GlutinEventLoop>,
     mut _ptr_window_builder: *mut ValueBox<WindowBuilder>,
     mut _ptr_context_builder: *mut ValueBox<GlutinContextBuilder>,
@@ -116,6 +270,10 @@ pub fn glutin_create_windowed_context(
         return std::ptr::null_mut();
     }
 
+    if _ptr_events_loop.check_owner_thread() != GlutinResult::Ok {
+        return std::ptr::null_mut();
+    }
+
     _ptr_events_loop
         .with_mut(|event_loop| {
             _ptr_context_builder
@@ -125,72 +283,59 @@ pub fn glutin_create_windowed_context(
                         debug!("Windowed context builder: {:?}", &context_builder);
                         debug!("Window builder: {:?}", &window_builder);
 
-                        context_builder
-                            .build_windowed(window_builder, event_loop)
-                            .map_err(|error| BoxerError::AnyError(error.into()))
+                        match &mut event_loop.backend {
+                            GlutinEventLoopBackend::Native(event_loop) => context_builder
+                                .build_windowed(window_builder, event_loop)
+                                .map_err(|error| BoxerError::AnyError(error.into())),
+                            GlutinEventLoopBackend::Mock(event_loop) => {
+                                let window = event_loop.create_window(window_builder);
+                                Ok(GlutinWindowedContext::Mock(window))
+                            }
+                        }
                     })
                 })
         })
         .into_raw()
+        .owned_by_current_thread()
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////
 ///////////////////////////// W I N D O W   A C C E S S O R S /////////////////////////
 ///////////////////////////////////////////////////////////////////////////////////////
 #[no_mangle]
-pub fn glutin_windowed_context_make_current(mut _ptr_window: *mut ValueBox<GlutinWindowedContext>) {
-    if _ptr_window.is_null() {
-        return;
+pub extern "C" fn glutin_windowed_context_make_current(
+    mut _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    let result = _ptr_window.check_owner_thread();
+    if result != GlutinResult::Ok {
+        return result;
     }
 
+    let mut context_result = Ok(());
     _ptr_window
-        .replace_value(|window| {
-            let context: GlutinWindowedContext;
-
-            match window.make_current() {
-                Ok(windowed_context) => context = windowed_context,
-                Err((windowed_context, err)) => {
-                    context = windowed_context;
-                    match err {
-                        ContextError::OsError(string) => {
-                            error!("OS Error in make_current: {}", string)
-                        }
-                        ContextError::IoError(error) => {
-                            error!("IO Error in make_current: {:?}", error)
-                        }
-                        ContextError::ContextLost => error!("ContextLost Error in make_current"),
-                        ContextError::FunctionUnavailable => {
-                            error!("FunctionUnavailable Error in make_current")
-                        }
-                    }
-                }
+        .replace_value(|window| match window.make_current() {
+            Ok(context) => context,
+            Err((context, error)) => {
+                context_result = Err(error);
+                context
             }
-            context
         })
-        .log();
+        .map_or(GlutinResult::NullPointer, |_| context_result.into())
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_swap_buffers(_ptr_window: *mut ValueBox<GlutinWindowedContext>) {
-    _ptr_window.with_not_null(|window| match window.swap_buffers() {
-        Ok(_) => {}
-        Err(err) => match err {
-            ContextError::OsError(string) => error!("OS Error in swap_buffers: {}", string),
-            ContextError::IoError(error) => error!("IO Error in swap_buffers: {:?}", error),
-            ContextError::ContextLost => error!("ContextLost Error in swap_buffers"),
-            ContextError::FunctionUnavailable => {
-                error!("FunctionUnavailable Error in swap_buffers")
-            }
-        },
-    });
+pub extern "C" fn glutin_windowed_context_swap_buffers(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| window.swap_buffers().into())
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_proc_address(
+pub extern "C" fn glutin_windowed_context_get_proc_address(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _ptr_symbol: *mut ValueBox<StringBox>,
 ) -> *const c_void {
-    _ptr_window.with_not_null_return(std::ptr::null(), |window| {
+    _ptr_window.with_owned_return(std::ptr::null(), |window| {
         _ptr_symbol.with_not_null_return(std::ptr::null(), |symbol| {
             window.get_proc_address(symbol.to_string().as_str())
         })
@@ -198,33 +343,36 @@ pub fn glutin_windowed_context_get_proc_address(
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_api(
+pub extern "C" fn glutin_windowed_context_get_api(
     _ptr_context: *mut ValueBox<GlutinWindowedContext>,
 ) -> ContextApi {
-    _ptr_context.with_not_null_return(ContextApi::Unknown, |context| context.get_api().into())
+    _ptr_context.with_owned_return(ContextApi::Unknown, |context| context.get_api().into())
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_raw_window_handle(
+pub extern "C" fn glutin_windowed_context_raw_window_handle(
     _ptr_context: *mut ValueBox<GlutinWindowedContext>,
 ) -> *mut ValueBox<RawWindowHandle> {
-    _ptr_context.with_not_null_return(std::ptr::null_mut(), |context| {
-        ValueBox::new(context.raw_window_handle()).into_raw()
+    _ptr_context.with_owned_return(std::ptr::null_mut(), |context| {
+        match context.raw_window_handle() {
+            None => std::ptr::null_mut(),
+            Some(handle) => ValueBox::new(handle).into_raw(),
+        }
     })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_is_current(
+pub extern "C" fn glutin_windowed_context_is_current(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
 ) -> bool {
-    _ptr_window.with_not_null_return(false, |window| window.is_current())
+    _ptr_window.with_owned_return(false, |window| window.is_current())
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_pixel_format(
+pub extern "C" fn glutin_windowed_context_get_pixel_format(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
 ) -> *mut ValueBox<PixelFormat> {
-    _ptr_window.with_not_null_return(std::ptr::null_mut(), |window| {
+    _ptr_window.with_owned_return(std::ptr::null_mut(), |window| {
         match window.get_pixel_format() {
             None => glutin_pixel_format_default(),
             Some(format) => ValueBox::new(format).into_raw(),
@@ -233,132 +381,358 @@ pub fn glutin_windowed_context_get_pixel_format(
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_resize(
+pub extern "C" fn glutin_windowed_context_resize(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _width: u32,
     _height: u32,
-) {
-    _ptr_window.with_not_null(|window| window.resize(PhysicalSize::new(_width, _height)));
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.resize(PhysicalSize::new(_width, _height));
+        GlutinResult::Ok
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_request_redraw(_ptr_window: *mut ValueBox<GlutinWindowedContext>) {
-    _ptr_window.with_not_null(|window| window.window().request_redraw());
+pub extern "C" fn glutin_windowed_context_request_redraw(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().request_redraw();
+        GlutinResult::Ok
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_scale_factor(
+pub extern "C" fn glutin_windowed_context_get_scale_factor(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
 ) -> f64 {
-    _ptr_window.with_not_null_return(1.0, |window| window.window().scale_factor())
+    _ptr_window.with_owned_return(1.0, |window| window.window().scale_factor())
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_inner_size(
+pub extern "C" fn glutin_windowed_context_get_inner_size(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _ptr_size: *mut ValueBox<SizeBox<u32>>,
-) {
-    _ptr_window.with_not_null(|window| {
-        _ptr_size.with_not_null(|size| {
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        _ptr_size.with_not_null_return(GlutinResult::NullPointer, |size| {
             let window_size: PhysicalSize<u32> = window.window().inner_size();
             size.width = window_size.width;
             size.height = window_size.height;
-        });
-    });
+            GlutinResult::Ok
+        })
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_set_inner_size(
+pub extern "C" fn glutin_windowed_context_set_inner_size(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _width: u32,
     _height: u32,
-) {
-    _ptr_window.with_not_null(|window| {
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
         window
             .window()
-            .set_inner_size(PhysicalSize::new(_width, _height))
-    });
+            .set_inner_size(PhysicalSize::new(_width, _height));
+        GlutinResult::Ok
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_position(
+pub extern "C" fn glutin_windowed_context_get_position(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _ptr_position: *mut ValueBox<PointBox<i32>>,
-) {
-    _ptr_window.with_not_null(|window| {
-        _ptr_position.with_not_null(|position| match window.window().outer_position() {
-            Ok(physical_position) => {
-                position.x = physical_position.x;
-                position.y = physical_position.y;
-            }
-            Err(err) => {
-                error!(
-                    "[glutin_windowed_context_get_position] Error getting position: {:?}",
-                    err
-                );
-                position.be_zero()
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        _ptr_position.with_not_null_return(GlutinResult::NullPointer, |position| {
+            match window.window().outer_position() {
+                Ok(physical_position) => {
+                    position.x = physical_position.x;
+                    position.y = physical_position.y;
+                    GlutinResult::Ok
+                }
+                Err(err) => {
+                    error!(
+                        "[glutin_windowed_context_get_position] Error getting position: {:?}",
+                        err
+                    );
+                    position.be_zero();
+                    GlutinResult::NotSupported
+                }
             }
         })
-    });
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_set_position(
+pub extern "C" fn glutin_windowed_context_set_position(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     x: i32,
     y: i32,
-) {
-    _ptr_window.with_not_null(|window| {
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
         window
             .window()
-            .set_outer_position(PhysicalPosition::new(x, y))
-    });
+            .set_outer_position(PhysicalPosition::new(x, y));
+        GlutinResult::Ok
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_get_id(
+pub extern "C" fn glutin_windowed_context_get_id(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _ptr_number: *mut ValueBox<U128Box>,
-) {
-    _ptr_window.with_not_null(|window| {
-        _ptr_number.with_not_null(|number| {
-            let id: U128Box = glutin_convert_window_id(window.window().id());
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        _ptr_number.with_not_null_return(GlutinResult::NullPointer, |number| {
+            let id: U128Box = window.window().window_id();
             number.low = id.low;
-            number.high = id.high
-        });
-    });
+            number.high = id.high;
+            GlutinResult::Ok
+        })
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_set_title(
+pub extern "C" fn glutin_windowed_context_set_title(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     _ptr_boxer_string: *mut ValueBox<StringBox>,
-) {
-    _ptr_window.with_not_null(|window| {
-        _ptr_boxer_string
-            .with_not_null(|string| window.window().set_title(string.to_string().as_ref()))
-    });
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        _ptr_boxer_string.with_not_null_return(GlutinResult::NullPointer, |string| {
+            window.window().set_title(string.to_string().as_ref());
+            GlutinResult::Ok
+        })
+    })
 }
 
 #[no_mangle]
-pub fn glutin_windowed_context_set_cursor_icon(
+pub extern "C" fn glutin_windowed_context_set_cursor_icon(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     cursor_icon: GlutinCursorIcon,
-) {
-    _ptr_window.with_not_null(|window| window.window().set_cursor_icon(cursor_icon.into()));
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_cursor_icon(cursor_icon.into());
+        GlutinResult::Ok
+    })
 }
 
+/// Set a custom cursor on the window. Returns `NotSupported` if custom cursors are not
+/// supported by the windowing system or the mock backend, in which case the host should fall
+/// back to a cursor icon. Use `glutin_windowed_context_set_cursor_icon` to switch back
+/// to a system cursor.
 #[no_mangle]
-pub fn glutin_windowed_context_set_maximized(
+pub extern "C" fn glutin_windowed_context_set_custom_cursor(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    _ptr_cursor: *mut ValueBox<GlutinCustomCursor>,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        _ptr_cursor.with_not_null_return(GlutinResult::NullPointer, |cursor| {
+            let native_window = match window.window().native_window() {
+                Some(native_window) if glutin_is_custom_cursor_supported(native_window) => {
+                    native_window
+                }
+                _ => return GlutinResult::NotSupported,
+            };
+            match cursor.set_on_window(native_window) {
+                Ok(_) => GlutinResult::Ok,
+                Err(error) => {
+                    error!("[glutin_windowed_context_set_custom_cursor] {}", error);
+                    GlutinResult::OsError
+                }
+            }
+        })
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_cursor_visible(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    visible: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_cursor_visible(visible);
+        GlutinResult::Ok
+    })
+}
+
+/// Grab the cursor, preventing it from leaving the window.
+/// On macOS the cursor is locked in place instead of being confined.
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_cursor_grab(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    grab: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| window.window().set_cursor_grab(grab).into())
+}
+
+/// Move the cursor to a given physical position relative to the window's inner area
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_cursor_position(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    x: f64,
+    y: f64,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window
+            .window()
+            .set_cursor_position(PhysicalPosition::new(x, y))
+            .into()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_maximized(
     _ptr_window: *mut ValueBox<GlutinWindowedContext>,
     maximized: bool,
-) {
-    _ptr_window.with_not_null(|window| {
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
         window.window().set_maximized(maximized);
-    });
+        GlutinResult::Ok
+    })
+}
+
+/// Set the physical position of the IME candidate window relative to the window's inner area,
+/// typically the position of the text cursor. On X11 this moves the XIM spot location.
+/// Composed text is delivered through `WindowEventReceivedCharacter` events, winit 0.26 reports
+/// neither preedit text nor a way to enable the input method per window.
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_ime_position(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    x: i32,
+    y: i32,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window
+            .window()
+            .set_ime_position(PhysicalPosition::new(x, y));
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_is_maximized(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| window.window().is_maximized())
+}
+
+/// Make the window borderless fullscreen on its current monitor, or restore it
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_fullscreen(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    fullscreen: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_fullscreen(fullscreen);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_is_fullscreen(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> bool {
+    _ptr_window.with_owned_return(false, |window| window.window().is_fullscreen())
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_minimized(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    minimized: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_minimized(minimized);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_visible(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    visible: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_visible(visible);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_focus_window(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().focus_window();
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_request_user_attention(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    attention_type: GlutinUserAttentionType,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window
+            .window()
+            .request_user_attention(attention_type.into());
+        GlutinResult::Ok
+    })
+}
+
+/// Start moving the window with the left mouse button until it is released.
+/// Must be called while the left mouse button is pressed, for example from a custom title bar.
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_drag_window(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| window.window().drag_window().into())
 }
 
 #[no_mangle]
-pub fn glutin_destroy_windowed_context(_ptr: *mut ValueBox<GlutinWindowedContext>) {
-    _ptr.release();
+pub extern "C" fn glutin_windowed_context_set_decorations(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    decorations: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_decorations(decorations);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_resizable(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    resizable: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_resizable(resizable);
+        GlutinResult::Ok
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn glutin_windowed_context_set_always_on_top(
+    _ptr_window: *mut ValueBox<GlutinWindowedContext>,
+    always_on_top: bool,
+) -> GlutinResult {
+    _ptr_window.with_owned_result(|window| {
+        window.window().set_always_on_top(always_on_top);
+        GlutinResult::Ok
+    })
+}
+
+/// Destroy the window. Returns `WrongThread` and leaks the window if called by another
+/// thread than the one that created it. Queued commands for the window are dropped.
+#[no_mangle]
+pub extern "C" fn glutin_destroy_windowed_context(
+    _ptr: *mut ValueBox<GlutinWindowedContext>,
+) -> GlutinResult {
+    _ptr.with_owned_return((), |window| {
+        if let Some(native_window) = window.window().native_window() {
+            glutin_release_window_cursors(native_window);
+        }
+    });
+    _ptr.release_owned()
 }